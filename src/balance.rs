@@ -0,0 +1,136 @@
+use std::fs;
+#[cfg(debug_assertions)]
+use std::time::{Instant, SystemTime};
+
+use crate::errors_to_result;
+use crate::inventory::ResourceKind;
+
+// tunable gameplay numbers, see assets/balance.toml
+#[derive (Clone, Deserialize)]
+#[serde(default)]
+pub struct Balance {
+    pub miss_chance: u8,
+
+    pub harpoon_damage: isize,
+    pub cannon_damage: isize,
+    pub broken_cannon_damage: isize,
+    pub pole_damage_multiplier: f32,
+
+    pub tree_wood: isize,
+    pub sapling_wood: isize,
+    // in seconds spent on the island
+    pub stump_regrowth: isize,
+    pub sapling_growth: isize,
+    pub deposit_hits: isize,
+    pub deposit_mineral: isize,
+
+    pub repair_wood_per_health: isize,
+    pub repair_mineral_per_shield: isize,
+
+    // most a boat can carry of each resource
+    pub wood_limit: isize,
+    pub mineral_limit: isize,
+    pub cloth_limit: isize,
+    pub gunpowder_limit: isize,
+    pub food_limit: isize,
+    pub gold_limit: isize
+}
+
+impl Default for Balance {
+    fn default() -> Balance {
+        Balance{miss_chance: 55, harpoon_damage: 7, cannon_damage: 2, broken_cannon_damage: 1, pole_damage_multiplier: 1.5,
+                tree_wood: 5, stump_regrowth: 300, sapling_growth: 90, sapling_wood: 2, deposit_hits: 4, deposit_mineral: 5, repair_wood_per_health: 5, repair_mineral_per_shield: 5,
+                wood_limit: 100, mineral_limit: 50, cloth_limit: 20, gunpowder_limit: 20, food_limit: 20, gold_limit: 500}
+    }
+}
+
+impl Balance {
+    // a missing file means the defaults, a broken one is an error
+    pub fn load(path: &str) -> Result<Balance, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => {
+                eprintln!("{} not found, using default balance", path);
+                return Ok(Balance::default());
+            }
+        };
+        let balance: Balance = toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut errors = Vec::new();
+        for (field, value) in &[("harpoon_damage", balance.harpoon_damage), ("cannon_damage", balance.cannon_damage),
+                                ("broken_cannon_damage", balance.broken_cannon_damage), ("tree_wood", balance.tree_wood),
+                                ("deposit_mineral", balance.deposit_mineral),
+                                ("stump_regrowth", balance.stump_regrowth), ("sapling_growth", balance.sapling_growth),
+                                ("sapling_wood", balance.sapling_wood)] {
+            if *value < 0 {
+                errors.push(format!("{} can't be negative", field));
+            }
+        }
+        for (field, value) in &[("deposit_hits", balance.deposit_hits), ("repair_wood_per_health", balance.repair_wood_per_health),
+                                ("repair_mineral_per_shield", balance.repair_mineral_per_shield), ("wood_limit", balance.wood_limit),
+                                ("mineral_limit", balance.mineral_limit), ("cloth_limit", balance.cloth_limit),
+                                ("gunpowder_limit", balance.gunpowder_limit), ("food_limit", balance.food_limit),
+                                ("gold_limit", balance.gold_limit)] {
+            if *value < 1 {
+                errors.push(format!("{} has to be at least 1", field));
+            }
+        }
+        if balance.pole_damage_multiplier < 0.0 {
+            errors.push("pole_damage_multiplier can't be negative".to_owned());
+        }
+
+        errors_to_result(path, errors, balance)
+    }
+
+    pub fn stack_limit(&self, kind: ResourceKind) -> isize {
+        match kind {
+            ResourceKind::WOOD => self.wood_limit,
+            ResourceKind::MINERAL => self.mineral_limit,
+            ResourceKind::CLOTH => self.cloth_limit,
+            ResourceKind::GUNPOWDER => self.gunpowder_limit,
+            ResourceKind::FOOD => self.food_limit,
+            ResourceKind::GOLD => self.gold_limit
+        }
+    }
+}
+
+// reloads the balance file when it changes on disk, only used in debug builds
+#[cfg(debug_assertions)]
+pub struct BalanceWatcher {
+    path: String,
+    modified: Option<SystemTime>,
+    last_check: Instant
+}
+
+#[cfg(debug_assertions)]
+impl BalanceWatcher {
+    pub fn new(path: &str) -> BalanceWatcher {
+        BalanceWatcher{path: path.to_owned(), modified: BalanceWatcher::modified(path), last_check: Instant::now()}
+    }
+
+    fn modified(path: &str) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    // checks about once a second, a file that fails to load keeps the old values
+    pub fn poll(&mut self, balance: &mut Balance) {
+        if self.last_check.elapsed().as_secs() < 1 {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        let modified = BalanceWatcher::modified(&self.path);
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        match Balance::load(&self.path) {
+            Ok(new) => {
+                *balance = new;
+                println!("reloaded {}", self.path);
+            },
+            Err(e) => eprintln!("{}", e)
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// plays lots of battles without a window to see how fair the enemies are,
+// every player loadout against every enemy archetype
+//
+//   pp-sim [--battles N] [--seed S] [--assets DIR] [--loadouts FILE] [--loadout NAME] [--enemy NAME]
+//          [--policy greedy|random] [--format csv|json]
+
+// enum variants are written in capitals all over, like AttackType::HARPOON
+#![allow(clippy::upper_case_acronyms)]
+
+extern crate rand;
+#[macro_use]
+extern crate serde_derive;
+extern crate ron;
+extern crate serde_json;
+extern crate pocket_pirates;
+
+use std::fs;
+
+use rand::prelude::*;
+
+use pocket_pirates::{Boat, AttackType, Target, check_ship, errors_to_result, rng_from_seed};
+use pocket_pirates::cli::{arg_value, parse_arg, resolve};
+use pocket_pirates::balance::Balance;
+use pocket_pirates::combat::{self, Command};
+use pocket_pirates::enemies::Enemies;
+use pocket_pirates::inventory::Inventory;
+use pocket_pirates::shipyard::{Hull, Shipyard};
+use pocket_pirates::textures::TextureRegistry;
+
+// a battle still going after this many turns counts as a draw
+const MAX_TURNS: u32 = 200;
+
+// a player boat to test, every hull in assets/hulls.ron and the ones in
+// assets/loadouts.ron
+#[derive (Deserialize)]
+struct Loadout {
+    name: String,
+    health: isize,
+    max_health: isize,
+    shield: isize,
+    attacks: Vec<AttackType>,
+    parts: Vec<Target>
+}
+
+#[derive (Deserialize)]
+struct Loadouts {
+    loadouts: Vec<Loadout>
+}
+
+impl Loadout {
+    // as it comes out of the shipyard
+    fn from_hull(hull: &Hull) -> Loadout {
+        Loadout{name: hull.name.clone(), health: hull.health, max_health: hull.health, shield: hull.shield,
+                attacks: hull.attacks.clone(), parts: hull.parts.clone()}
+    }
+
+    fn boat(&self) -> Boat {
+        Boat::new(self.health, self.max_health, self.shield, &self.attacks, &self.parts, Inventory::default(), None)
+    }
+}
+
+// reads and checks the loadouts
+fn load_loadouts(path: &str) -> Result<Vec<Loadout>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let loadouts: Loadouts = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut errors = Vec::new();
+    for l in &loadouts.loadouts {
+        if l.health < 1 || l.health > l.max_health || l.shield < 0 {
+            errors.push(format!("\"{}\": health has to be between 1 and max_health and shield can't be negative", l.name));
+        }
+        check_ship(&l.name, &l.attacks, &l.parts, &mut errors);
+    }
+
+    errors_to_result(path, errors, loadouts.loadouts)
+}
+
+#[derive (Copy, Clone, PartialEq)]
+enum Policy {
+    // harpoon, then net, then shoot the helm off and keep hitting the pole
+    GREEDY,
+    // picks like the enemy does
+    RANDOM
+}
+
+fn choose_player_command<R: Rng>(policy: Policy, player: &Boat, enemy: &Boat, rng: &mut R) -> Command {
+    match policy {
+        Policy::RANDOM => combat::choose_enemy_command(enemy, player, rng),
+        Policy::GREEDY => {
+            if player.enabled_attacks.contains(&AttackType::HARPOON) {
+                return Command{attack: AttackType::HARPOON, target: Target::NONE};
+            }
+            if player.enabled_attacks.contains(&AttackType::NET) && enemy.can_attack >= 0 {
+                return Command{attack: AttackType::NET, target: Target::NONE};
+            }
+
+            // same as the combat menu, every part the enemy has can be aimed at
+            let target =
+                if enemy.enabled_parts.contains(&Target::HELM) {
+                    Target::HELM
+                }
+                else if enemy.parts.contains(&Target::POLE) {
+                    Target::POLE
+                }
+                else {
+                    *enemy.parts.iter().next().unwrap()
+                };
+            Command{attack: AttackType::NORMAL, target}
+        }
+    }
+}
+
+// the data files and how the player plays, the same for every battle
+struct Rules {
+    textures: TextureRegistry,
+    enemies: Enemies,
+    balance: Balance,
+    policy: Policy
+}
+
+// how a loadout fared against an archetype
+#[derive (Serialize)]
+struct Stats {
+    loadout: String,
+    archetype: String,
+    battles: u32,
+    wins: u32,
+    losses: u32,
+    draws: u32,
+    win_rate: f32,
+    avg_turns: f32,
+    // battles by the player's health at the end, sunk is 0
+    health: Vec<u32>
+}
+
+// plays a single battle, (won, turns, player health left)
+fn battle<R: Rng>(mut player: Boat, mut enemy: Boat, rules: &Rules, rng: &mut R) -> (Option<bool>, u32, isize) {
+    for turn in 1..=MAX_TURNS {
+        let player_command = choose_player_command(rules.policy, &player, &enemy, rng);
+        let enemy_command = combat::choose_enemy_command(&player, &enemy, rng);
+        let report = combat::resolve_turn(&mut player, &mut enemy, player_command, enemy_command, &rules.balance, rng);
+
+        if report.player_sunk {
+            return (Some(false), turn, 0);
+        }
+        if report.enemy_sunk {
+            return (Some(true), turn, player.health);
+        }
+    }
+
+    (None, MAX_TURNS, player.health)
+}
+
+fn simulate<R: Rng>(loadout: &Loadout, archetype: &str, battles: u32, rules: &Rules, rng: &mut R) -> Stats {
+    let mut stats = Stats{loadout: loadout.name.clone(), archetype: archetype.to_owned(), battles, wins: 0, losses: 0, draws: 0,
+                          win_rate: 0.0, avg_turns: 0.0, health: vec!(0; loadout.max_health as usize + 1)};
+    let mut turns = 0;
+
+    for _ in 0..battles {
+        let enemy = rules.enemies.spawn_named(archetype, &rules.textures, rng).unwrap();
+        let (won, battle_turns, health) = battle(loadout.boat(), enemy, rules, rng);
+
+        match won {
+            Some(true) => stats.wins += 1,
+            Some(false) => stats.losses += 1,
+            None => stats.draws += 1
+        }
+        turns += battle_turns;
+        stats.health[health.max(0) as usize] += 1;
+    }
+
+    if battles > 0 {
+        stats.win_rate = stats.wins as f32 / battles as f32;
+        stats.avg_turns = turns as f32 / battles as f32;
+    }
+
+    stats
+}
+
+fn print_csv(stats: &[Stats]) {
+    let columns = stats.iter().map(|s| s.health.len()).max().unwrap_or(0);
+
+    let mut header = "loadout,archetype,battles,wins,losses,draws,win_rate,avg_turns".to_owned();
+    for health in 0..columns {
+        header += &format!(",health_{}", health);
+    }
+    println!("{}", header);
+
+    for s in stats {
+        let mut row = format!("\"{}\",\"{}\",{},{},{},{},{:.4},{:.2}", s.loadout, s.archetype, s.battles, s.wins, s.losses, s.draws, s.win_rate, s.avg_turns);
+        for health in 0..columns {
+            row += &format!(",{}", s.health.get(health).cloned().unwrap_or(0));
+        }
+        println!("{}", row);
+    }
+}
+
+// no filter lets everything through
+fn matches_filter(filter: &Option<String>, name: &str) -> bool {
+    match filter {
+        Some(filter) => filter == name,
+        None => true
+    }
+}
+
+fn run() -> Result<(), String> {
+    let battles = parse_arg("--battles", "a number")?.unwrap_or(10000);
+    let seed = parse_arg("--seed", "an unsigned 64-bit integer")?.unwrap_or_else(random::<u64>);
+    let policy = match arg_value("--policy").as_deref() {
+        Some("greedy") | None => Policy::GREEDY,
+        Some("random") => Policy::RANDOM,
+        Some(other) => return Err(format!("unknown policy \"{}\", expected greedy or random", other))
+    };
+    let json = match arg_value("--format").as_deref() {
+        Some("csv") | None => false,
+        Some("json") => true,
+        Some(other) => return Err(format!("unknown format \"{}\", expected csv or json", other))
+    };
+
+    // found next to the executable like the game does, so it runs from anywhere
+    let assets = resolve(&arg_value("--assets").unwrap_or_else(|| "assets".to_owned()));
+    let asset = |name: &str| assets.join(name).to_string_lossy().into_owned();
+
+    let textures = TextureRegistry::load(&asset("manifest.ron"))?;
+    let enemies = Enemies::load(&asset("enemies.ron"), &textures)?;
+    let balance = Balance::load(&asset("balance.toml"))?;
+    let rules = Rules{textures, enemies, balance, policy};
+    let shipyard = Shipyard::load(&asset("hulls.ron"), &rules.textures, &rules.balance)?;
+    let mut loadouts: Vec<_> = shipyard.hulls.iter().map(Loadout::from_hull).collect();
+    loadouts.extend(load_loadouts(&arg_value("--loadouts").unwrap_or_else(|| asset("loadouts.ron")))?);
+
+    let loadout_filter = arg_value("--loadout");
+    let loadouts: Vec<_> = loadouts.iter().filter(|l| matches_filter(&loadout_filter, &l.name)).collect();
+    let enemy_filter = arg_value("--enemy");
+    let archetypes: Vec<_> = rules.enemies.names().into_iter().filter(|a| matches_filter(&enemy_filter, a)).collect();
+    if loadouts.is_empty() || archetypes.is_empty() {
+        return Err("no loadout or no archetype matches".to_owned());
+    }
+
+    // the seed goes to stderr so it doesn't end up in the results
+    eprintln!("seed: {}", seed);
+    let mut rng = rng_from_seed(seed);
+
+    let mut stats = Vec::new();
+    for loadout in &loadouts {
+        for archetype in &archetypes {
+            stats.push(simulate(loadout, archetype, battles, &rules, &mut rng));
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?);
+    }
+    else {
+        print_csv(&stats);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
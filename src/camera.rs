@@ -0,0 +1,138 @@
+use sdl2::rect::Rect;
+
+use crate::{TILE_WIDTH, TILE_HEIGHT, HALF_TILE_WIDTH, HALF_TILE_HEIGHT, CAMERA_FOLLOW, CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM, CAMERA_ZOOM_STEP};
+
+// top left corner of a tile's sprite, in world pixels where tile 0, 0 is at the origin
+pub fn iso_to_world(x: isize, y: isize) -> (isize, isize) {
+    (x * HALF_TILE_WIDTH - y * HALF_TILE_WIDTH,
+     x * HALF_TILE_HEIGHT + y * HALF_TILE_HEIGHT)
+}
+
+// the tile whose ground has world point x, y
+pub fn world_to_iso(x: f32, y: f32) -> (isize, isize) {
+    // in tiles from the middle of tile 0, 0 along the screen's axes
+    let across = (x - HALF_TILE_WIDTH as f32) / HALF_TILE_WIDTH as f32;
+    let down = (y - HALF_TILE_HEIGHT as f32) / HALF_TILE_HEIGHT as f32;
+    (((down + across) / 2.0).round() as isize, ((down - across) / 2.0).round() as isize)
+}
+
+// the world pixels covered by a width x height map
+pub fn map_bounds(width: usize, height: usize) -> Rect {
+    let (width, height) = (width as isize, height as isize);
+    let left = iso_to_world(0, height - 1).0;
+    let right = iso_to_world(width - 1, 0).0 + TILE_WIDTH;
+    let bottom = iso_to_world(width - 1, height - 1).1 + TILE_HEIGHT;
+    rect!(left, 0, right - left, bottom)
+}
+
+// what part of the world a frame shows, scenes draw everything through one
+#[derive (Copy, Clone)]
+pub struct View {
+    // world point at the top left corner of the screen
+    x: f32,
+    y: f32,
+    zoom: f32
+}
+
+impl View {
+    // shows the whole of world inside area of the screen, as big as the zoom limits allow
+    pub fn framing(world: Rect, area: Rect) -> View {
+        let zoom = (area.width() as f32 / world.width() as f32).min(area.height() as f32 / world.height() as f32);
+        let zoom = zoom.clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
+        View::centered(world.center().x() as f32, world.center().y() as f32, zoom, area)
+    }
+
+    // world point x, y in the middle of area
+    fn centered(x: f32, y: f32, zoom: f32, area: Rect) -> View {
+        View{x: x - (area.x() as f32 + area.width() as f32 / 2.0) / zoom,
+             y: y - (area.y() as f32 + area.height() as f32 / 2.0) / zoom,
+             zoom}
+    }
+
+    pub fn to_screen(self, x: isize, y: isize) -> (isize, isize) {
+        (((x as f32 - self.x) * self.zoom).round() as isize, ((y as f32 - self.y) * self.zoom).round() as isize)
+    }
+
+    pub fn to_world(self, x: i32, y: i32) -> (f32, f32) {
+        (x as f32 / self.zoom + self.x, y as f32 / self.zoom + self.y)
+    }
+
+    // the tile under screen point x, y
+    pub fn tile_at(self, x: i32, y: i32) -> (isize, isize) {
+        let (world_x, world_y) = self.to_world(x, y);
+        world_to_iso(world_x, world_y)
+    }
+
+    // a rect in world pixels, both corners are rounded so neighbouring tiles don't leave gaps
+    pub fn rect(&self, x: isize, y: isize, width: u32, height: u32) -> Rect {
+        let (left, top) = self.to_screen(x, y);
+        let (right, bottom) = self.to_screen(x + width as isize, y + height as isize);
+        rect!(left, top, (right - left).max(1), (bottom - top).max(1))
+    }
+
+    // the sprite of a tile or of an object standing on one
+    pub fn tile_rect(&self, x: isize, y: isize, anchor: (isize, isize), size: (u32, u32)) -> Rect {
+        let (world_x, world_y) = iso_to_world(x, y);
+        self.rect(world_x + anchor.0, world_y + anchor.1, size.0, size.1)
+    }
+}
+
+// follows a point around the island, eased over a few ticks, and zooms in and out
+pub struct Camera {
+    // world point in the middle of the screen
+    x: f32,
+    y: f32,
+    zoom: f32,
+    // the same a tick ago, frames are drawn in between
+    last: (f32, f32, f32),
+    // what zoom is eased towards
+    target_zoom: f32
+}
+
+impl Camera {
+    pub fn new(x: f32, y: f32) -> Camera {
+        Camera{x, y, zoom: 1.0, last: (x, y, 1.0), target_zoom: 1.0}
+    }
+
+    // the world point in the middle of tile x, y
+    pub fn tile_center(x: isize, y: isize) -> (f32, f32) {
+        let (world_x, world_y) = iso_to_world(x, y);
+        ((world_x + HALF_TILE_WIDTH) as f32, (world_y + HALF_TILE_HEIGHT) as f32)
+    }
+
+    // positive steps zoom in
+    pub fn zoom_by(&mut self, steps: i32) {
+        self.target_zoom = (self.target_zoom + steps as f32 * CAMERA_ZOOM_STEP).clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
+    }
+
+    // called once per tick with where the camera should end up
+    pub fn follow(&mut self, x: f32, y: f32) {
+        self.last = (self.x, self.y, self.zoom);
+        self.x += (x - self.x) * CAMERA_FOLLOW;
+        self.y += (y - self.y) * CAMERA_FOLLOW;
+        self.zoom += (self.target_zoom - self.zoom) * CAMERA_FOLLOW;
+    }
+
+    // the view of a screen screen_width x screen_height, kept inside bounds
+    // unless the screen is bigger than them, then they're centered instead
+    pub fn view(&self, alpha: f32, screen_width: u32, screen_height: u32, bounds: Rect) -> View {
+        let (last_x, last_y, last_zoom) = self.last;
+        let x = last_x + (self.x - last_x) * alpha;
+        let y = last_y + (self.y - last_y) * alpha;
+        let zoom = last_zoom + (self.zoom - last_zoom) * alpha;
+
+        let keep_inside = |center: f32, half_screen: f32, low: i32, high: i32| {
+            let (low, high) = (low as f32, high as f32);
+            if high - low < half_screen * 2.0 {
+                (low + high) / 2.0
+            }
+            else {
+                center.clamp(low + half_screen, high - half_screen)
+            }
+        };
+        let x = keep_inside(x, screen_width as f32 / zoom / 2.0, bounds.left(), bounds.right());
+        let y = keep_inside(y, screen_height as f32 / zoom / 2.0, bounds.top(), bounds.bottom());
+
+        View::centered(x, y, zoom, rect!(0, 0, screen_width, screen_height))
+    }
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+// value following a command line flag, like --seed 42
+pub fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    args.iter().position(|arg| arg == flag).map(|i| args.get(i + 1).cloned().unwrap_or_default())
+}
+
+pub fn has_flag(flag: &str) -> bool {
+    env::args().any(|arg| arg == flag)
+}
+
+// value of a flag parsed as T, the error names the flag
+pub fn parse_arg<T: std::str::FromStr>(flag: &str, expected: &str) -> Result<Option<T>, String> {
+    match arg_value(flag) {
+        Some(s) => s.parse().map(Some).map_err(|_| format!("{} expects {}", flag, expected)),
+        None => Ok(None)
+    }
+}
+
+// directories above the executable's searched by resolve, enough to get from
+// target/debug or target/release to the repository
+const RESOLVE_PARENTS: usize = 2;
+
+// a relative path is tried next to the executable and then in the few
+// directories above it, so target/debug finds the repository's assets,
+// falling back to the working directory when none of them has it
+pub fn resolve(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    if let Ok(exe) = env::current_exe() {
+        for dir in exe.ancestors().skip(1).take(RESOLVE_PARENTS + 1) {
+            let candidate = dir.join(path);
+            if candidate.exists() {
+                return candidate;
+            }
+        }
+    }
+
+    path.to_path_buf()
+}
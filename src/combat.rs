@@ -0,0 +1,271 @@
+use rand::Rng;
+
+use crate::{Boat, AttackType, Target};
+use crate::balance::Balance;
+
+// turns a boat loses after being caught in a net
+const NET_STUN: i32 = -2;
+
+#[derive (Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Command {
+    pub attack: AttackType,
+    pub target: Target
+}
+
+#[derive (Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Outcome {
+    NONE, // sank before it could fire
+    STUNNED,
+    MISSED,
+    HIT(isize) // damage before shields, nets always deal 0
+}
+
+impl Outcome {
+    pub fn fired(self) -> bool {
+        !matches!(self, Outcome::NONE | Outcome::STUNNED)
+    }
+}
+
+#[derive (Copy, Clone, Debug)]
+pub struct TurnReport {
+    pub player_command: Command,
+    pub player_outcome: Outcome,
+    pub enemy_command: Command,
+    pub enemy_outcome: Outcome,
+
+    pub player_sunk: bool,
+    pub enemy_sunk: bool
+}
+
+// returns true if boat died
+pub fn do_damage(boat : &mut Boat, damage: isize) -> bool {
+    let mut damage = damage;
+
+    // shield
+    if boat.shield > 0 {
+        boat.shield -= damage;
+        if boat.shield < 0 {
+            damage = boat.shield.abs();
+            boat.shield = 0;
+        } else {
+            damage = 0;
+        }
+    }
+
+    boat.health -= damage;
+
+    boat.health <= 0
+}
+
+pub fn choose_enemy_command<R: Rng>(player : &Boat, enemy : &Boat, rng : &mut R) -> Command {
+    let i = rng.gen_range(0, enemy.enabled_attacks.len());
+    let attack = *enemy.enabled_attacks.iter().nth(i).unwrap();
+
+    let target =
+        if attack == AttackType::NORMAL {
+            let i = rng.gen_range(0, player.parts.len());
+            *player.parts.iter().nth(i).unwrap()
+        }
+        else {
+            Target::NONE
+        };
+
+    Command{attack, target}
+}
+
+fn cannon_damage(boat : &Boat, balance : &Balance) -> isize {
+    let mut damage = 0;
+    for cannon in &[Target::CANNON1, Target::CANNON2] {
+        if boat.enabled_parts.contains(cannon) {
+            damage += balance.cannon_damage;
+        }
+        else if boat.parts.contains(cannon) {
+            damage += balance.broken_cannon_damage;
+        }
+    }
+
+    damage
+}
+
+fn fire<R: Rng>(attacker : &mut Boat, defender : &mut Boat, command : Command, balance : &Balance, rng : &mut R) -> Outcome {
+    if attacker.can_attack < 0 {
+        attacker.can_attack += 1;
+        return Outcome::STUNNED;
+    }
+
+    let miss =
+        if defender.enabled_parts.contains(&Target::HELM) {
+            balance.miss_chance
+        }
+        else {
+            0
+        };
+    let hit = rng.gen::<u8>() >= miss;
+
+    match command.attack {
+        AttackType::NORMAL => {
+            if !hit {
+                return Outcome::MISSED;
+            }
+
+            let mut damage = cannon_damage(attacker, balance);
+            if command.target == Target::POLE {
+                damage = (damage as f32 * balance.pole_damage_multiplier) as isize;
+            }
+            defender.enabled_parts.remove(&command.target);
+
+            do_damage(defender, damage);
+            Outcome::HIT(damage)
+        },
+        AttackType::HARPOON => {
+            attacker.enabled_attacks.remove(&AttackType::HARPOON);
+            if !hit {
+                return Outcome::MISSED;
+            }
+
+            do_damage(defender, balance.harpoon_damage);
+            Outcome::HIT(balance.harpoon_damage)
+        },
+        AttackType::NET => {
+            attacker.enabled_attacks.remove(&AttackType::NET);
+            if !hit {
+                return Outcome::MISSED;
+            }
+
+            defender.can_attack = NET_STUN;
+            Outcome::HIT(0)
+        }
+    }
+}
+
+// the player always fires first, a sunk enemy doesn't fire back
+pub fn resolve_turn<R: Rng>(player : &mut Boat, enemy : &mut Boat, player_command : Command, enemy_command : Command,
+                                balance : &Balance, rng : &mut R) -> TurnReport {
+    let player_outcome = fire(player, enemy, player_command, balance, rng);
+
+    let enemy_sunk = enemy.health <= 0;
+    let enemy_outcome =
+        if enemy_sunk {
+            // TODO: shipwreck
+            // what doesn't fit in the player's hold sinks, the enemy's is left
+            // with what was taken so the battle result can show it
+            enemy.inventory = player.inventory.add_all(&enemy.inventory, balance);
+
+            Outcome::NONE
+        }
+        else {
+            fire(enemy, player, enemy_command, balance, rng)
+        };
+
+    TurnReport{player_command, player_outcome, enemy_command, enemy_outcome,
+               player_sunk: player.health <= 0, enemy_sunk}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng_from_seed;
+    use crate::inventory::{Inventory, ResourceKind};
+
+    fn boat(health: isize, shield: isize, attacks: &[AttackType], parts: &[Target]) -> Boat {
+        Boat::new(health, health, shield, attacks, parts, Inventory::default(), None)
+    }
+
+    fn balance(miss_chance: u8) -> Balance {
+        Balance{miss_chance, ..Balance::default()}
+    }
+
+    const CANNON: Command = Command{attack: AttackType::NORMAL, target: Target::CANNON1};
+    const NET: Command = Command{attack: AttackType::NET, target: Target::NONE};
+
+    #[test]
+    fn cannon_hit_damages_and_breaks_the_target() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let mut enemy = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let balance = balance(0);
+
+        let outcome = fire(&mut player, &mut enemy, CANNON, &balance, &mut rng_from_seed(1));
+        assert_eq!(outcome, Outcome::HIT(balance.cannon_damage));
+        assert_eq!(enemy.health, 10 - balance.cannon_damage);
+        assert!(!enemy.enabled_parts.contains(&Target::CANNON1));
+    }
+
+    #[test]
+    fn miss_leaves_the_target_alone() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let mut enemy = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+
+        // only a roll of 255 hits
+        let outcome = fire(&mut player, &mut enemy, CANNON, &balance(255), &mut rng_from_seed(1));
+        assert_eq!(outcome, Outcome::MISSED);
+        assert_eq!(enemy.health, 10);
+        assert!(enemy.enabled_parts.contains(&Target::CANNON1));
+    }
+
+    #[test]
+    fn stunned_boat_loses_its_turn() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let mut enemy = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        player.can_attack = -1;
+
+        let outcome = fire(&mut player, &mut enemy, CANNON, &balance(0), &mut rng_from_seed(1));
+        assert_eq!(outcome, Outcome::STUNNED);
+        assert_eq!(player.can_attack, 0);
+        assert_eq!(enemy.health, 10);
+    }
+
+    #[test]
+    fn shield_absorbs_damage_first() {
+        let mut target = boat(10, 3, &[AttackType::NORMAL], &[Target::HELM]);
+        assert!(!do_damage(&mut target, 2));
+        assert_eq!((target.shield, target.health), (1, 10));
+
+        assert!(!do_damage(&mut target, 4));
+        assert_eq!((target.shield, target.health), (0, 7));
+
+        assert!(do_damage(&mut target, 7));
+        assert_eq!(target.health, 0);
+    }
+
+    #[test]
+    fn net_stuns_for_two_turns() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL, AttackType::NET], &[Target::HELM, Target::CANNON1]);
+        let mut enemy = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let balance = balance(0);
+        let mut rng = rng_from_seed(1);
+
+        assert_eq!(fire(&mut player, &mut enemy, NET, &balance, &mut rng), Outcome::HIT(0));
+        assert_eq!(enemy.can_attack, NET_STUN);
+        assert!(!player.enabled_attacks.contains(&AttackType::NET));
+
+        for _ in 0..NET_STUN.abs() {
+            assert_eq!(fire(&mut enemy, &mut player, CANNON, &balance, &mut rng), Outcome::STUNNED);
+        }
+        assert!(fire(&mut enemy, &mut player, CANNON, &balance, &mut rng).fired());
+    }
+
+    #[test]
+    fn pole_shots_are_multiplied() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1, Target::CANNON2]);
+        let mut enemy = boat(20, 0, &[AttackType::NORMAL], &[Target::HELM, Target::POLE]);
+        let balance = balance(0);
+
+        let command = Command{attack: AttackType::NORMAL, target: Target::POLE};
+        let damage = (2.0 * balance.cannon_damage as f32 * balance.pole_damage_multiplier) as isize;
+        assert_eq!(fire(&mut player, &mut enemy, command, &balance, &mut rng_from_seed(1)), Outcome::HIT(damage));
+        assert_eq!(enemy.health, 20 - damage);
+    }
+
+    #[test]
+    fn sunk_enemy_doesnt_fire_back() {
+        let mut player = boat(10, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        let mut enemy = boat(1, 0, &[AttackType::NORMAL], &[Target::HELM, Target::CANNON1]);
+        enemy.inventory = Inventory::new(&[(ResourceKind::WOOD, 7)]);
+
+        let report = resolve_turn(&mut player, &mut enemy, CANNON, CANNON, &balance(0), &mut rng_from_seed(1));
+        assert!(report.enemy_sunk);
+        assert_eq!(report.enemy_outcome, Outcome::NONE);
+        assert_eq!(player.health, 10);
+        assert_eq!(player.inventory.get(ResourceKind::WOOD), 7);
+    }
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{errors_to_result, WINDOW_WIDTH, WINDOW_HEIGHT, MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT, FONT_SIZE};
+use crate::cli::{arg_value, has_flag, parse_arg, resolve};
+
+// window, audio and where the files are, read from config.toml in the
+// config directory and overridden by the command line
+#[derive (Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    // draws at the default window size and scales that to fit the window,
+    // otherwise bigger windows just show more
+    pub logical_size: bool,
+    // music, from 0 to 1
+    pub volume: f32,
+
+    // relative paths are looked up next to the executable, see cli::resolve
+    pub assets: String,
+    pub font: String,
+    pub font_size: u16,
+
+    // locale code, like pt-BR
+    pub language: Option<String>
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config{width: WINDOW_WIDTH, height: WINDOW_HEIGHT, fullscreen: false, vsync: true, logical_size: false, volume: 1.0,
+               assets: "assets".to_owned(), font: "roboto.ttf".to_owned(), font_size: FONT_SIZE, language: None}
+    }
+}
+
+// <config dir>/pocket-pirates/config.toml
+fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("pocket-pirates").join("config.toml"))
+}
+
+impl Config {
+    // --config <file> or the default file, a missing default file means the
+    // defaults, a broken file is an error
+    pub fn load() -> Result<Config, String> {
+        let (path, required) = match arg_value("--config") {
+            Some(path) => (Some(PathBuf::from(path)), true),
+            None => (default_path(), false)
+        };
+
+        let mut config = match path {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?,
+                Err(e) => {
+                    if required {
+                        return Err(format!("{}: {}", path.display(), e));
+                    }
+                    Config::default()
+                }
+            },
+            None => Config::default()
+        };
+
+        config.apply_args()?;
+        config.check()?;
+        Ok(config)
+    }
+
+    //   --width W --height H --fullscreen --windowed --vsync --no-vsync
+    //   --logical-size --no-logical-size
+    //   --volume V --assets DIR --font FILE --font-size N --lang CODE
+    fn apply_args(&mut self) -> Result<(), String> {
+        if let Some(width) = parse_arg("--width", "a width in pixels")? {
+            self.width = width;
+        }
+        if let Some(height) = parse_arg("--height", "a height in pixels")? {
+            self.height = height;
+        }
+        if has_flag("--fullscreen") {
+            self.fullscreen = true;
+        }
+        if has_flag("--windowed") {
+            self.fullscreen = false;
+        }
+        if has_flag("--vsync") {
+            self.vsync = true;
+        }
+        if has_flag("--no-vsync") {
+            self.vsync = false;
+        }
+        if has_flag("--logical-size") {
+            self.logical_size = true;
+        }
+        if has_flag("--no-logical-size") {
+            self.logical_size = false;
+        }
+        if let Some(volume) = parse_arg("--volume", "a number from 0 to 1")? {
+            self.volume = volume;
+        }
+        if let Some(assets) = arg_value("--assets") {
+            self.assets = assets;
+        }
+        if let Some(font) = arg_value("--font") {
+            self.font = font;
+        }
+        if let Some(font_size) = parse_arg("--font-size", "a size in points")? {
+            self.font_size = font_size;
+        }
+        if let Some(language) = arg_value("--lang") {
+            self.language = Some(language);
+        }
+
+        Ok(())
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        if self.width < MIN_WINDOW_WIDTH || self.height < MIN_WINDOW_HEIGHT {
+            errors.push(format!("the window has to be at least {}x{}", MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT));
+        }
+        if self.volume < 0.0 || self.volume > 1.0 {
+            errors.push("volume has to be between 0 and 1".to_owned());
+        }
+        // the small font is a quarter smaller
+        if self.font_size < 4 {
+            errors.push("font_size has to be at least 4".to_owned());
+        }
+
+        errors_to_result("config", errors, ())
+    }
+
+    // a file in the asset directory, like asset("balance.toml")
+    pub fn asset(&self, name: &str) -> String {
+        resolve(&self.assets).join(name).to_string_lossy().into_owned()
+    }
+
+    pub fn font_path(&self) -> String {
+        resolve(&self.font).to_string_lossy().into_owned()
+    }
+
+    pub fn small_font_size(&self) -> u16 {
+        self.font_size - self.font_size / 4
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use rand::Rng;
+
+use crate::{Boat, Object, AttackType, Target, check_ship, errors_to_result, BOAT_ENEMY_COMBAT_X, BOAT_ENEMY_COMBAT_Y};
+use crate::inventory::{Inventory, ResourceKind};
+use crate::textures::{Direction, TextureRegistry};
+
+// one kind of enemy ship, see assets/enemies.ron
+#[derive (Clone, Deserialize)]
+struct Archetype {
+    name: String,
+    weight: u32,
+
+    health: (isize, isize),
+    shield: (isize, isize),
+    // carried and given up when sunk, kinds left out aren't carried
+    #[serde(default)]
+    loot: BTreeMap<ResourceKind, (isize, isize)>,
+
+    attacks: Vec<AttackType>,
+    parts: Vec<Target>,
+    sprites: Vec<String>
+}
+
+#[derive (Clone, Deserialize)]
+pub struct Enemies {
+    archetypes: Vec<Archetype>
+}
+
+impl Enemies {
+    // reads and checks the archetypes
+    pub fn load(path: &str, textures: &TextureRegistry) -> Result<Enemies, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let enemies: Enemies = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut errors = Vec::new();
+        if enemies.archetypes.iter().all(|a| a.weight == 0) {
+            errors.push("at least one archetype needs a weight above 0".to_owned());
+        }
+        for a in &enemies.archetypes {
+            for (field, range) in &[("health", a.health), ("shield", a.shield)] {
+                if range.0 > range.1 || range.0 < 0 {
+                    errors.push(format!("\"{}\": bad {} range {:?}", a.name, field, range));
+                }
+            }
+            for (kind, range) in &a.loot {
+                if range.0 > range.1 || range.0 < 0 {
+                    errors.push(format!("\"{}\": bad {:?} loot range {:?}", a.name, kind, range));
+                }
+            }
+            if a.health.0 < 1 {
+                errors.push(format!("\"{}\": health has to be at least 1", a.name));
+            }
+            check_ship(&a.name, &a.attacks, &a.parts, &mut errors);
+            if a.sprites.is_empty() {
+                errors.push(format!("\"{}\": no sprites", a.name));
+            }
+            for sprite in &a.sprites {
+                match textures.find(sprite) {
+                    Some(id) => {
+                        if !textures.has_variant(id, Direction::NW) || !textures.has_variant(id, Direction::SE) {
+                            errors.push(format!("\"{}\": sprite \"{}\" needs NW and SE variants", a.name, sprite));
+                        }
+                    },
+                    None => errors.push(format!("\"{}\": sprite \"{}\" isn't in the manifest", a.name, sprite))
+                }
+            }
+        }
+
+        errors_to_result(path, errors, enemies)
+    }
+
+    // picks an archetype by weight and rolls a ship from it
+    pub fn spawn<R: Rng>(&self, textures: &TextureRegistry, rng: &mut R) -> Boat {
+        let total: u32 = self.archetypes.iter().map(|a| a.weight).sum();
+        let mut roll = rng.gen_range(0, total);
+        let mut archetype = &self.archetypes[0];
+        for a in &self.archetypes {
+            if roll < a.weight {
+                archetype = a;
+                break;
+            }
+            roll -= a.weight;
+        }
+
+        roll_boat(archetype, textures, rng)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.archetypes.iter().map(|a| a.name.clone()).collect()
+    }
+
+    // rolls a ship from the archetype with that name, ignoring weights
+    pub fn spawn_named<R: Rng>(&self, name: &str, textures: &TextureRegistry, rng: &mut R) -> Option<Boat> {
+        self.archetypes.iter().find(|a| a.name == name).map(|archetype| roll_boat(archetype, textures, rng))
+    }
+}
+
+fn roll_boat<R: Rng>(archetype: &Archetype, textures: &TextureRegistry, rng: &mut R) -> Boat {
+    let health = rng.gen_range(archetype.health.0, archetype.health.1 + 1);
+    let shield = rng.gen_range(archetype.shield.0, archetype.shield.1 + 1);
+    let loot: Vec<_> = archetype.loot.iter().map(|(kind, range)| (*kind, rng.gen_range(range.0, range.1 + 1))).collect();
+    let sprite = &archetype.sprites[rng.gen_range(0, archetype.sprites.len())];
+
+    Boat::new(health, health, shield, &archetype.attacks, &archetype.parts, Inventory::new(&loot),
+              Some(Object{texture: textures.id(sprite), dir: Direction::NW, x: BOAT_ENEMY_COMBAT_X, y: BOAT_ENEMY_COMBAT_Y}))
+}
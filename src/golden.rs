@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use sdl2::image::{LoadSurface, SaveSurface};
+use sdl2::surface::Surface;
+
+use crate::{GameState, errors_to_result, FONT_SIZE, WINDOW_WIDTH, WINDOW_HEIGHT};
+use crate::inventory::Inventory;
+use crate::locale::DEFAULT_LOCALE;
+use crate::render::{self, SdlRenderer};
+use crate::scene::Scene;
+use crate::scene::island::IslandScene;
+use crate::scene::combat::CombatScene;
+
+// the frames are always rendered from this seed, changing it means updating the pngs
+pub const GOLDEN_SEED: u64 = 1;
+// per channel, text can come out slightly different between SDL_ttf versions
+const GOLDEN_TOLERANCE: u8 = 2;
+
+// renders the island and combat scenes offscreen and compares them with the
+// pngs in dir, or overwrites those when update is set, the window size and
+// font size in the config are ignored so the frames stay comparable
+pub fn run(mut state: GameState, font_path: &str, dir: &str, update: bool) -> Result<(), String> {
+    state.locales.select(DEFAULT_LOCALE)?;
+
+    let _sdl_context = sdl2::init()?;
+    let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
+    let _image_context = sdl2::image::init(sdl2::image::INIT_PNG)?;
+
+    let font = ttf_context.load_font(font_path, FONT_SIZE)?;
+    let small_font = ttf_context.load_font(font_path, FONT_SIZE - FONT_SIZE / 4)?;
+
+    let canvas = render::software_canvas(WINDOW_WIDTH, WINDOW_HEIGHT)?;
+    let texture_creator = canvas.texture_creator();
+    let mut renderer = SdlRenderer::new(canvas, &texture_creator, &state.textures, font, small_font);
+
+    let mut errors = Vec::new();
+
+    IslandScene::new(&state.island, &state.textures).draw(&state, &mut renderer, 0.0);
+    check(&renderer.frame()?, dir, "island", update, &mut errors)?;
+
+    // there is no boat until one is built, this is the first hull fresh from the shipyard
+    let (x, y) = state.island.dock;
+    state.player_boat = state.shipyard.hulls[0].build(&state.textures, Inventory::default(), x, y);
+    CombatScene::new(&state.player_boat).draw(&state, &mut renderer, 0.0);
+    check(&renderer.frame()?, dir, "combat", update, &mut errors)?;
+
+    errors_to_result(dir, errors, ())
+}
+
+fn check(frame: &Surface, dir: &str, name: &str, update: bool, errors: &mut Vec<String>) -> Result<(), String> {
+    let path = Path::new(dir).join(format!("{}.png", name));
+    if update {
+        println!("writing {}", path.display());
+        return frame.save(&path);
+    }
+
+    if !path.is_file() {
+        errors.push(format!("{} doesn't exist, run with --update-golden to create it", path.display()));
+        return Ok(());
+    }
+
+    let golden = Surface::from_file(&path)?.convert(&frame.pixel_format())?;
+    let differing = differing_pixels(frame, &golden);
+    if differing > 0 {
+        let actual = Path::new(dir).join(format!("{}.actual.png", name));
+        frame.save(&actual)?;
+        errors.push(format!("{}.png: {} pixels differ, see {}", name, differing, actual.display()));
+    }
+
+    Ok(())
+}
+
+// both surfaces have to be in the same 32 bit format
+fn differing_pixels(a: &Surface, b: &Surface) -> usize {
+    if a.width() != b.width() || a.height() != b.height() {
+        return (a.width() * a.height()) as usize;
+    }
+
+    let row_bytes = a.width() as usize * 4;
+    a.with_lock(|a_pixels| b.with_lock(|b_pixels| {
+        let mut differing = 0;
+        for y in 0..a.height() as usize {
+            let a_row = &a_pixels[y * a.pitch() as usize..][..row_bytes];
+            let b_row = &b_pixels[y * b.pitch() as usize..][..row_bytes];
+            for (a_pixel, b_pixel) in a_row.chunks(4).zip(b_row.chunks(4)) {
+                if a_pixel.iter().zip(b_pixel).any(|(a, b)| (*a as i16 - *b as i16).abs() > GOLDEN_TOLERANCE as i16) {
+                    differing += 1;
+                }
+            }
+        }
+        differing
+    }))
+}
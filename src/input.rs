@@ -0,0 +1,61 @@
+use sdl2::keyboard::{KeyboardState, Scancode};
+
+use crate::save::SaveGame;
+use crate::scene::combat::ButtonType;
+use crate::textures::Direction;
+
+// something the player did, scenes turn events into these right away (clicks
+// are resolved against the layout on screen) and act on them on the next tick
+#[derive (Clone, Serialize, Deserialize)]
+pub enum Action {
+    // E on the island, gathers or sets sail
+    INTERACT,
+    // B on the island, opens the shipyard
+    BUILD,
+    // R on the island, plants a sapling
+    PLANT,
+    // click on the island, the tile to walk to
+    WALK(isize, isize),
+    // mouse wheel or +/- on the island, positive zooms in
+    ZOOM(i32),
+    // combat menu
+    BUTTON(ButtonType),
+    // a button of the battle result or a hull in the shipyard
+    CHOICE(usize),
+    // Esc or B in the shipyard
+    CLOSE,
+    // new game in a slot
+    NEW(usize),
+    // switches to the next language
+    LANGUAGE,
+    // the slot is read when clicked, so a replay carries the save with it
+    CONTINUE(usize, Box<SaveGame>),
+    // seed of the next run
+    RESTART(u64),
+    QUIT
+}
+
+// all a scene gets to see of the player during one tick
+#[derive (Clone, Default, Serialize, Deserialize)]
+pub struct TickInput {
+    // directions of the WASD keys held down
+    pub walking: Vec<Direction>,
+    pub actions: Vec<Action>
+}
+
+impl TickInput {
+    pub fn from_keyboard(keys: &KeyboardState, actions: Vec<Action>) -> TickInput {
+        let walk_keys = [(Scancode::W, Direction::NE), (Scancode::A, Direction::NW), (Scancode::S, Direction::SW), (Scancode::D, Direction::SE)];
+        let walking = walk_keys.iter().filter(|(key, _)| keys.is_scancode_pressed(*key)).map(|(_, dir)| *dir).collect();
+
+        TickInput{walking, actions}
+    }
+
+    pub fn is_walking(&self, dir: Direction) -> bool {
+        self.walking.contains(&dir)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.walking.is_empty() && self.actions.is_empty()
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use crate::balance::Balance;
+
+#[derive (Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum ResourceKind {
+    WOOD,
+    MINERAL,
+    CLOTH,
+    GUNPOWDER,
+    FOOD,
+    GOLD
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 6] = [ResourceKind::WOOD, ResourceKind::MINERAL, ResourceKind::CLOTH,
+                                        ResourceKind::GUNPOWDER, ResourceKind::FOOD, ResourceKind::GOLD];
+
+    // sprite in the manifest and string in the locales
+    pub fn name(self) -> &'static str {
+        match self {
+            ResourceKind::WOOD => "wood",
+            ResourceKind::MINERAL => "mineral",
+            ResourceKind::CLOTH => "cloth",
+            ResourceKind::GUNPOWDER => "gunpowder",
+            ResourceKind::FOOD => "food",
+            ResourceKind::GOLD => "gold"
+        }
+    }
+}
+
+// what a boat carries, kinds it has none of are left out
+#[derive (Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Inventory(BTreeMap<ResourceKind, isize>);
+
+impl Inventory {
+    // starting contents, not held to the stack limits
+    pub fn new(amounts: &[(ResourceKind, isize)]) -> Inventory {
+        Inventory(amounts.iter().cloned().filter(|(_, amount)| *amount > 0).collect())
+    }
+
+    pub fn get(&self, kind: ResourceKind) -> isize {
+        self.0.get(&kind).cloned().unwrap_or(0)
+    }
+
+    pub fn is_full(&self, kind: ResourceKind, balance: &Balance) -> bool {
+        self.get(kind) >= balance.stack_limit(kind)
+    }
+
+    // as much of amount as fits under the stack limit, returns how much that was
+    pub fn add(&mut self, kind: ResourceKind, amount: isize, balance: &Balance) -> isize {
+        let have = self.get(kind);
+        let added = amount.min(balance.stack_limit(kind) - have).max(0);
+        if added > 0 {
+            self.0.insert(kind, have + added);
+        }
+
+        added
+    }
+
+    // all of amount or nothing, returns whether there was enough
+    pub fn take(&mut self, kind: ResourceKind, amount: isize) -> bool {
+        let have = self.get(kind);
+        if have < amount {
+            return false;
+        }
+
+        if have == amount {
+            self.0.remove(&kind);
+        }
+        else {
+            self.0.insert(kind, have - amount);
+        }
+        true
+    }
+
+    pub fn has_all(&self, cost: &BTreeMap<ResourceKind, isize>) -> bool {
+        cost.iter().all(|(kind, amount)| self.get(*kind) >= *amount)
+    }
+
+    // every amount in cost or nothing, returns whether there was enough of all of them
+    pub fn take_all(&mut self, cost: &BTreeMap<ResourceKind, isize>) -> bool {
+        if !self.has_all(cost) {
+            return false;
+        }
+
+        for (kind, amount) in cost {
+            self.take(*kind, *amount);
+        }
+        true
+    }
+
+    // adds everything in other that fits, returns what did
+    pub fn add_all(&mut self, other: &Inventory, balance: &Balance) -> Inventory {
+        let mut added = Inventory::default();
+        for (kind, amount) in other.iter() {
+            let n = self.add(kind, amount, balance);
+            if n > 0 {
+                added.0.insert(kind, n);
+            }
+        }
+
+        added
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // in ResourceKind order
+    pub fn iter(&self) -> impl Iterator<Item = (ResourceKind, isize)> + '_ {
+        self.0.iter().map(|(kind, amount)| (*kind, *amount))
+    }
+}
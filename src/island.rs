@@ -0,0 +1,546 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use std::f32::consts::PI;
+use std::fs;
+
+use rand::Rng;
+
+use crate::{Boat, Object, errors_to_result, TICKS_PER_SECOND};
+use crate::balance::Balance;
+use crate::inventory::ResourceKind;
+use crate::textures::{Direction, TextureId, TextureRegistry};
+
+// tile kinds stored in the map
+pub const GRASS: usize = 0;
+pub const WATER: usize = 2;
+pub const SAND: usize = 3;
+
+// as written by Tiled, only the parts the game uses
+#[derive (Deserialize)]
+struct TiledMap {
+    orientation: String,
+    width: usize,
+    height: usize,
+    tileheight: f32,
+    layers: Vec<TiledLayer>,
+    tilesets: Vec<TiledTileset>
+}
+
+#[derive (Deserialize)]
+struct TiledLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    data: TiledData,
+    // csv when missing, the json format writes csv data as an array
+    #[serde(default)]
+    encoding: Option<String>,
+    #[serde(default)]
+    compression: Option<String>,
+    #[serde(default)]
+    objects: Vec<TiledObject>
+}
+
+// an array of gids, or a base64 string when the layer format is set to that,
+// which is only recognized to report it
+#[derive (Deserialize)]
+#[serde(untagged)]
+enum TiledData {
+    GIDS(Vec<u32>),
+    ENCODED(serde::de::IgnoredAny)
+}
+
+impl Default for TiledData {
+    fn default() -> TiledData {
+        TiledData::GIDS(Vec::new())
+    }
+}
+
+#[derive (Deserialize)]
+struct TiledTileset {
+    firstgid: u32,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    tiles: Vec<TiledTile>
+}
+
+// newer Tiled versions call "type" "class"
+#[derive (Deserialize)]
+struct TiledTile {
+    id: u32,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String
+}
+
+#[derive (Deserialize)]
+struct TiledObject {
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f32,
+    y: f32
+}
+
+// the top bits of a gid are flip flags
+const TILED_GID_MASK: u32 = 0x1fff_ffff;
+
+fn tile_kind(name: &str) -> Option<usize> {
+    match name {
+        "grass" => Some(GRASS),
+        "water" => Some(WATER),
+        "sand" => Some(SAND),
+        _ => None
+    }
+}
+
+// names an object on the island for as long as it's there, ids aren't reused
+#[derive (Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub struct EntityId(u32);
+
+// the sprites grow() swaps between, looked up once instead of every tick
+#[derive (Copy, Clone)]
+pub struct GrowthSprites {
+    stump: TextureId,
+    sapling: TextureId,
+    tree: TextureId
+}
+
+impl GrowthSprites {
+    pub fn new(textures: &TextureRegistry) -> GrowthSprites {
+        GrowthSprites{stump: textures.id("stump"), sapling: textures.id("sapling"), tree: textures.id("tree")}
+    }
+}
+
+#[derive (Clone, Serialize, Deserialize)]
+pub struct Island {
+    pub map: Vec<Vec<usize>>, // map[x][y]
+    pub objects: BTreeMap<EntityId, Object>,
+    pub player: EntityId,
+    next_id: u32,
+    pub dock: (isize, isize),
+    // hits taken so far by the mineral deposits that have been started on
+    #[serde(default)]
+    pub mined: BTreeMap<EntityId, isize>,
+    // ticks left until a stump or sapling turns into a tree
+    #[serde(default)]
+    pub growing: BTreeMap<EntityId, isize>
+}
+
+impl Island {
+    // objects[player] is the player, every object gets the next id in order
+    fn new(map: Vec<Vec<usize>>, objects: Vec<Object>, player: usize, dock: (isize, isize)) -> Island {
+        let mut island = Island{map, objects: BTreeMap::new(), player: EntityId(player as u32), next_id: 0, dock, mined: BTreeMap::new(), growing: BTreeMap::new()};
+        for obj in objects {
+            island.add(obj);
+        }
+        island
+    }
+
+    pub fn add(&mut self, obj: Object) -> EntityId {
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
+        self.objects.insert(id, obj);
+        id
+    }
+
+    pub fn player(&self) -> Object {
+        self.objects[&self.player]
+    }
+
+    pub fn player_mut(&mut self) -> &mut Object {
+        self.objects.get_mut(&self.player).unwrap()
+    }
+
+    // called once per tick on the island, stumps and saplings from a map start growing on the first one
+    pub fn grow(&mut self, sprites: &GrowthSprites, balance: &Balance) {
+        for (id, obj) in &self.objects {
+            if !self.growing.contains_key(id) {
+                if obj.texture == sprites.stump {
+                    self.growing.insert(*id, balance.stump_regrowth * TICKS_PER_SECOND as isize);
+                }
+                else if obj.texture == sprites.sapling {
+                    self.growing.insert(*id, balance.sapling_growth * TICKS_PER_SECOND as isize);
+                }
+            }
+        }
+
+        let tree = sprites.tree;
+        let player = self.player();
+        let objects = &mut self.objects;
+        self.growing.retain(|id, ticks| {
+            *ticks -= 1;
+            if *ticks > 0 {
+                return true;
+            }
+            // a tree can't come up under the player, it waits for them to step off
+            let obj = &objects[id];
+            if obj.x == player.x && obj.y == player.y {
+                *ticks = 1;
+                return true;
+            }
+            objects.get_mut(id).unwrap().texture = tree;
+            false
+        });
+    }
+
+    // something stands on x, y that can't be walked through, stumps and saplings
+    // are stepped over so cutting and planting never wall the player in
+    pub fn is_blocked(&self, x: isize, y: isize) -> bool {
+        self.objects.iter().any(|(id, obj)| obj.x == x && obj.y == y && !self.growing.contains_key(id))
+    }
+
+    pub fn object_at(&self, x: isize, y: isize) -> Option<EntityId> {
+        self.objects.iter().find(|(_, obj)| obj.x == x && obj.y == y).map(|(id, _)| *id)
+    }
+
+    // loads a Tiled json map: the tile layer's tiles need a type of grass, water or sand,
+    // objects need a type of player, dock or the name of a sprite in the manifest without variants
+    pub fn load(path: &str, textures: &TextureRegistry) -> Result<Island, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let tiled: TiledMap = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+        if tiled.width == 0 || tiled.height == 0 {
+            return Err(format!("{}: empty map", path));
+        }
+
+        let mut errors = Vec::new();
+
+        // tiles are laid out and drawn as diamonds, other orientations would come out scrambled
+        if tiled.orientation != "isometric" {
+            errors.push(format!("orientation is \"{}\", only isometric maps are supported", tiled.orientation));
+        }
+
+        // gid -> tile kind
+        let mut kinds = HashMap::new();
+        for tileset in &tiled.tilesets {
+            if let Some(source) = &tileset.source {
+                errors.push(format!("external tileset {} isn't supported, embed it in the map", source));
+            }
+            for tile in &tileset.tiles {
+                let name = if tile.class.is_empty() { &tile.kind } else { &tile.class };
+                match tile_kind(name) {
+                    Some(kind) => { kinds.insert(tileset.firstgid + tile.id, kind); },
+                    None => errors.push(format!("tile {} has unknown type \"{}\"", tileset.firstgid + tile.id, name))
+                }
+            }
+        }
+
+        let mut map = vec!(vec!(WATER; tiled.height); tiled.width);
+        match tiled.layers.iter().find(|l| l.kind == "tilelayer") {
+            Some(layer) => {
+                let data = match &layer.data {
+                    TiledData::GIDS(data) => {
+                        if data.len() != tiled.width * tiled.height {
+                            errors.push(format!("tile layer has {} tiles, expected {}x{}", data.len(), tiled.width, tiled.height));
+                        }
+                        data.as_slice()
+                    },
+                    TiledData::ENCODED(_) => {
+                        let mut format = layer.encoding.clone().unwrap_or_default();
+                        if let Some(compression) = layer.compression.as_ref().filter(|c| !c.is_empty()) {
+                            format += &format!(", {} compressed", compression);
+                        }
+                        errors.push(format!("tile layer data is {}, only CSV (array) data is supported, set the tile layer format to CSV in the map properties", format));
+                        &[]
+                    }
+                };
+                for (i, gid) in data.iter().enumerate().take(tiled.width * tiled.height) {
+                    let gid = gid & TILED_GID_MASK;
+                    // empty cells are sea
+                    if gid == 0 {
+                        continue;
+                    }
+                    match kinds.get(&gid) {
+                        Some(kind) => map[i % tiled.width][i / tiled.width] = *kind,
+                        None => errors.push(format!("unknown tile {} at {}, {}", gid, i % tiled.width, i / tiled.width))
+                    }
+                }
+            },
+            None => errors.push("no tile layer".to_owned())
+        }
+
+        let mut objects = Vec::new();
+        let mut player = None;
+        let mut dock = None;
+        for obj in tiled.layers.iter().filter(|l| l.kind == "objectgroup").flat_map(|l| l.objects.iter()) {
+            let kind = if obj.class.is_empty() { &obj.kind } else { &obj.class };
+            // isometric object positions are in tile heights along both axes
+            let x = (obj.x / tiled.tileheight).floor() as isize;
+            let y = (obj.y / tiled.tileheight).floor() as isize;
+            if x < 0 || y < 0 || x >= tiled.width as isize || y >= tiled.height as isize {
+                errors.push(format!("\"{}\" at {}, {} is outside the map", kind, x, y));
+                continue;
+            }
+
+            match kind.as_str() {
+                "player" => {
+                    if player.is_some() {
+                        errors.push("more than one player".to_owned());
+                    }
+                    player = Some(objects.len());
+                    objects.push(Object{texture: textures.id("player"), dir: Direction::NE, x, y});
+                },
+                "dock" => {
+                    if dock.is_some() {
+                        errors.push("more than one dock".to_owned());
+                    }
+                    dock = Some((x, y));
+                },
+                // objects on the island don't turn, a sprite that only comes in directions can't be drawn
+                _ => match textures.find(kind) {
+                    Some(texture) if textures.has_variant(texture, Direction::NONE) => objects.push(Object{texture, dir: Direction::NONE, x, y}),
+                    Some(_) => errors.push(format!("sprite \"{}\" at {}, {} has variants, only sprites without a direction can be placed", kind, x, y)),
+                    None => errors.push(format!("object type \"{}\" is neither player, dock nor a sprite", kind))
+                }
+            }
+        }
+        if player.is_none() {
+            errors.push("no player object".to_owned());
+        }
+        if dock.is_none() {
+            errors.push("no dock object".to_owned());
+        }
+
+        errors_to_result(path, errors, ())?;
+        Ok(Island::new(map, objects, player.unwrap(), dock.unwrap()))
+    }
+}
+
+// size of generated islands, including the sea around them, the coast
+// radius stays well inside it
+const GEN_SIZE: usize = 20;
+// the coast is this many tiles from the center, give or take the noise
+const GEN_MIN_RADIUS: f32 = 4.0;
+const GEN_MAX_RADIUS: f32 = 8.0;
+// points around the coast that get their own random radius
+const GEN_COAST_POINTS: usize = 10;
+// islands thrown away before giving up, too little room for the trees needed
+// fails every time
+const GEN_MAX_ATTEMPTS: usize = 1000;
+
+impl Island {
+    // random island for the given rng: sea, a ring of sand and grass inside, with
+    // enough reachable trees for wood_needed and a dock on the beach
+    pub fn generate<R: Rng>(rng: &mut R, textures: &TextureRegistry, balance: &Balance, wood_needed: isize) -> Result<Island, String> {
+        let trees_needed =
+            if balance.tree_wood > 0 {
+                (wood_needed + balance.tree_wood - 1) / balance.tree_wood
+            }
+            else {
+                0
+            };
+
+        for _ in 0..GEN_MAX_ATTEMPTS {
+            if let Some(island) = Island::try_generate(rng, textures, trees_needed.max(1) as usize) {
+                return Ok(island);
+            }
+        }
+
+        Err(format!("no island with {} reachable trees after {} attempts, the cheapest hull needs {} wood and trees give {}",
+                    trees_needed.max(1), GEN_MAX_ATTEMPTS, wood_needed, balance.tree_wood))
+    }
+
+    fn try_generate<R: Rng>(rng: &mut R, textures: &TextureRegistry, trees_needed: usize) -> Option<Island> {
+        let center = (GEN_SIZE / 2) as f32;
+
+        // coast radius at evenly spaced angles, interpolated in between
+        let radii: Vec<f32> = (0..GEN_COAST_POINTS).map(|_| rng.gen_range(GEN_MIN_RADIUS, GEN_MAX_RADIUS)).collect();
+        let coast = |x: f32, y: f32| {
+            let angle = (y.atan2(x) + PI) / (2.0 * PI) * GEN_COAST_POINTS as f32;
+            let i = angle.floor() as usize % GEN_COAST_POINTS;
+            let t = angle - angle.floor();
+            radii[i] * (1.0 - t) + radii[(i + 1) % GEN_COAST_POINTS] * t
+        };
+
+        let mut map = vec!(vec!(WATER; GEN_SIZE); GEN_SIZE);
+        for (x, column) in map.iter_mut().enumerate() {
+            for (y, tile) in column.iter_mut().enumerate() {
+                let (dx, dy) = (x as f32 - center, y as f32 - center);
+                if (dx * dx + dy * dy).sqrt() < coast(dx, dy) {
+                    *tile = GRASS;
+                }
+            }
+        }
+
+        // land next to the sea is beach
+        let mut beach = Vec::new();
+        for x in 0..GEN_SIZE as isize {
+            for y in 0..GEN_SIZE as isize {
+                let neighbours = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)];
+                if tile_at(&map, x, y) != WATER && neighbours.iter().any(|&(nx, ny)| tile_at(&map, nx, ny) == WATER) {
+                    beach.push((x, y));
+                }
+            }
+        }
+        for &(x, y) in &beach {
+            map[x as usize][y as usize] = SAND;
+        }
+
+        let mut free: Vec<(isize, isize)> = Vec::new();
+        for x in 0..GEN_SIZE as isize {
+            for y in 0..GEN_SIZE as isize {
+                if tile_at(&map, x, y) == GRASS {
+                    free.push((x, y));
+                }
+            }
+        }
+        let trees = trees_needed + rng.gen_range(2, 7);
+        let minerals = rng.gen_range(2, 5);
+        if free.len() < trees + minerals + 1 {
+            return None;
+        }
+
+        let dock = beach[rng.gen_range(0, beach.len())];
+
+        // everything is placed on distinct grass tiles, the dock is on sand so it stays clear
+        let i = rng.gen_range(0, free.len());
+        let player = free.swap_remove(i);
+        let mut objects = vec!(Object{texture: textures.id("player"), dir: Direction::NE, x: player.0, y: player.1});
+
+        let tree = textures.id("tree");
+        let mineral = textures.id("mineral_deposit");
+        for n in 0..trees + minerals {
+            let i = rng.gen_range(0, free.len());
+            let (x, y) = free.swap_remove(i);
+            objects.push(Object{texture: if n < trees { tree } else { mineral }, dir: Direction::NONE, x, y});
+        }
+
+        // trees can be cut down to stumps that are walked over, minerals stay in the way
+        let mut reached = vec!(vec!(false; GEN_SIZE); GEN_SIZE);
+        let mut queue = VecDeque::new();
+        reached[player.0 as usize][player.1 as usize] = true;
+        queue.push_back(player);
+        while let Some((x, y)) = queue.pop_front() {
+            for &(nx, ny) in &[(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if tile_at(&map, nx, ny) == WATER || reached[nx as usize][ny as usize] {
+                    continue;
+                }
+                if objects.iter().any(|o| o.texture == mineral && o.x == nx && o.y == ny) {
+                    continue;
+                }
+                reached[nx as usize][ny as usize] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+
+        let reachable_trees = objects.iter().filter(|o| o.texture == tree && reached[o.x as usize][o.y as usize]).count();
+        if reachable_trees < trees_needed || !reached[dock.0 as usize][dock.1 as usize] {
+            return None;
+        }
+
+        Some(Island::new(map, objects, 0, dock))
+    }
+}
+
+// anything outside the map counts as sea
+pub fn tile_at(map: &[Vec<usize>], x: isize, y: isize) -> usize {
+    if x < 0 || y < 0 || x as usize >= map.len() || y as usize >= map[x as usize].len() {
+        return WATER;
+    }
+    map[x as usize][y as usize]
+}
+
+// steps from one tile to a neighbour, the way the player faces while taking them
+pub fn step_delta(dir: Direction) -> (isize, isize) {
+    match dir {
+        Direction::NE => (0, -1),
+        Direction::NW => (-1, 0),
+        Direction::SW => (0, 1),
+        Direction::SE => (1, 0),
+        Direction::NONE => (0, 0)
+    }
+}
+
+// A* over the land, objects are in the way except for whatever stands on from,
+// the path doesn't include from and is None when to can't be reached
+pub fn find_path(island: &Island, from: (isize, isize), to: (isize, isize)) -> Option<Vec<(isize, isize)>> {
+    let walkable = |(x, y): (isize, isize)| tile_at(&island.map, x, y) != WATER && !island.is_blocked(x, y);
+    if !walkable(to) {
+        return None;
+    }
+    let distance = |(x, y): (isize, isize)| (x - to.0).abs() + (y - to.1).abs();
+
+    let mut came_from = HashMap::new();
+    let mut cost = HashMap::new();
+    let mut open = BinaryHeap::new();
+    cost.insert(from, 0);
+    open.push(Reverse((distance(from), from)));
+
+    while let Some(Reverse((_, pos))) = open.pop() {
+        if pos == to {
+            let mut path = vec!(to);
+            let mut cur = to;
+            while let Some(&prev) = came_from.get(&cur) {
+                if prev == from {
+                    break;
+                }
+                path.push(prev);
+                cur = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let next_cost = cost[&pos] + 1;
+        for dir in &[Direction::NE, Direction::NW, Direction::SW, Direction::SE] {
+            let (dx, dy) = step_delta(*dir);
+            let next = (pos.0 + dx, pos.1 + dy);
+            if !walkable(next) || matches!(cost.get(&next), Some(&c) if c <= next_cost) {
+                continue;
+            }
+            cost.insert(next, next_cost);
+            came_from.insert(next, pos);
+            open.push(Reverse((next_cost + distance(next), next)));
+        }
+    }
+
+    None
+}
+
+// cuts down the tree or hits the mineral deposit the player is facing, if there is one
+// and the boat has room for what it gives
+pub fn gather_resource(textures: &TextureRegistry, balance: &Balance, island: &mut Island, player_boat: &mut Boat) {
+    let player = island.player();
+    let (dx, dy) = step_delta(player.dir);
+
+    let tree = textures.id("tree");
+    let deposit = textures.id("mineral_deposit");
+    let inventory = &mut player_boat.inventory;
+    match island.object_at(player.x + dx, player.y + dy) {
+        Some(id) if island.objects[&id].texture == tree && !inventory.is_full(ResourceKind::WOOD, balance) => {
+            island.objects.get_mut(&id).unwrap().texture = textures.id("stump");
+            island.growing.insert(id, balance.stump_regrowth * TICKS_PER_SECOND as isize);
+            inventory.add(ResourceKind::WOOD, balance.tree_wood, balance);
+        },
+        Some(id) if island.objects[&id].texture == deposit && !inventory.is_full(ResourceKind::MINERAL, balance) => {
+            let hits = island.mined.get(&id).cloned().unwrap_or(0) + 1;
+            if hits >= balance.deposit_hits {
+                island.objects.remove(&id);
+                island.mined.remove(&id);
+                inventory.add(ResourceKind::MINERAL, balance.deposit_mineral, balance);
+            }
+            else {
+                island.mined.insert(id, hits);
+            }
+        },
+        _ => ()
+    }
+}
+
+// plants a sapling on the grass the player is facing, for some wood
+pub fn plant_sapling(textures: &TextureRegistry, balance: &Balance, island: &mut Island, player_boat: &mut Boat) {
+    let player = island.player();
+    let (dx, dy) = step_delta(player.dir);
+    let (x, y) = (player.x + dx, player.y + dy);
+
+    if tile_at(&island.map, x, y) != GRASS || island.object_at(x, y).is_some() || (x, y) == island.dock ||
+       !player_boat.inventory.take(ResourceKind::WOOD, balance.sapling_wood) {
+        return;
+    }
+
+    let id = island.add(Object{texture: textures.id("sapling"), dir: Direction::NONE, x, y});
+    island.growing.insert(id, balance.sapling_growth * TICKS_PER_SECOND as isize);
+}
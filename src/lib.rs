@@ -0,0 +1,119 @@
+// the rules of the game that don't need a window: boats, what they carry and
+// the shipyard building them, combat, enemies, balance and the sprite
+// manifest. shared by the game and the pp-sim tool
+
+// enum variants are written in capitals all over, like AttackType::HARPOON
+#![allow(clippy::upper_case_acronyms)]
+
+extern crate rand;
+#[macro_use]
+extern crate serde_derive;
+extern crate ron;
+extern crate toml;
+
+use std::collections::BTreeSet;
+
+use rand::prelude::*;
+
+pub mod balance;
+pub mod cli;
+pub mod combat;
+pub mod enemies;
+pub mod inventory;
+pub mod shipyard;
+pub mod textures;
+
+use inventory::Inventory;
+use textures::{Direction, TextureId};
+
+pub const BOAT_PLAYER_COMBAT_X: isize = 9;
+pub const BOAT_PLAYER_COMBAT_Y: isize = 12;
+pub const BOAT_ENEMY_COMBAT_X: isize = 10;
+pub const BOAT_ENEMY_COMBAT_Y: isize = 4;
+
+#[derive (Copy, Clone, Serialize, Deserialize)]
+pub struct Object {
+    pub texture: TextureId,
+    pub dir: Direction,
+
+    pub x: isize,
+    pub y: isize
+}
+
+// the default is no boat at all, nothing to sail or fight with
+#[derive (Clone, Default, Serialize, Deserialize)]
+pub struct Boat {
+    pub health: isize,
+    pub max_health: isize,
+    pub shield: isize,
+
+    pub inventory: Inventory,
+
+    pub obj: Option<Object>,
+
+    pub attacks: BTreeSet<AttackType>,
+    pub enabled_attacks: BTreeSet<AttackType>,
+    pub parts: BTreeSet<Target>,
+    pub enabled_parts: BTreeSet<Target>,
+
+    pub can_attack: i32
+}
+
+impl Boat {
+    // ready for battle, with every attack and part working
+    pub fn new(health: isize, max_health: isize, shield: isize, attacks: &[AttackType], parts: &[Target],
+               inventory: Inventory, obj: Option<Object>) -> Boat {
+        Boat{health, max_health, shield, inventory, obj, can_attack: 0,
+             attacks: attacks.iter().cloned().collect(),
+             enabled_attacks: attacks.iter().cloned().collect(),
+             parts: parts.iter().cloned().collect(),
+             enabled_parts: parts.iter().cloned().collect()}
+    }
+}
+
+#[derive (Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum AttackType {
+    NORMAL,
+    NET,
+    HARPOON,
+}
+
+#[derive (Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum Target {
+    NONE,
+    CANNON1,
+    CANNON2,
+    HELM,
+    POLE
+}
+
+pub fn rng_from_seed(seed: u64) -> StdRng {
+    // StdRng takes a 32 byte seed, the u64 fills the first 8
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate().take(8) {
+        *byte = (seed >> (i * 8)) as u8;
+    }
+
+    StdRng::from_seed(bytes)
+}
+
+// what every ship needs, wherever it's described, problems go to errors
+pub fn check_ship(name: &str, attacks: &[AttackType], parts: &[Target], errors: &mut Vec<String>) {
+    if !attacks.contains(&AttackType::NORMAL) {
+        errors.push(format!("\"{}\": every ship needs the NORMAL attack", name));
+    }
+    if parts.is_empty() || parts.contains(&Target::NONE) {
+        errors.push(format!("\"{}\": parts can't be empty or contain NONE", name));
+    }
+}
+
+// how the data files are checked: every problem found is reported at once,
+// under the file they're in
+pub fn errors_to_result<T>(path: &str, errors: Vec<String>, value: T) -> Result<T, String> {
+    if errors.is_empty() {
+        Ok(value)
+    }
+    else {
+        Err(format!("{}:\n  {}", path, errors.join("\n  ")))
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::fs;
+
+use crate::errors_to_result;
+
+// the game was written in Portuguese, other locales are checked against it
+pub const DEFAULT_LOCALE: &str = "pt-BR";
+
+// one file in assets/locales, the file name is the locale code
+#[derive (Clone, Deserialize)]
+struct Locale {
+    #[serde(skip)]
+    code: String,
+    name: String,
+    strings: HashMap<String, String>
+}
+
+// every locale found, and the one the text is shown in
+#[derive (Clone)]
+pub struct Locales {
+    locales: Vec<Locale>,
+    current: usize
+}
+
+// names between braces, "Novo jogo {slot}" has slot
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        match rest[start..].find('}') {
+            Some(end) => {
+                names.insert(&rest[start + 1..start + end]);
+                rest = &rest[start + end + 1..];
+            },
+            None => break
+        }
+    }
+
+    names
+}
+
+impl Locales {
+    // reads every .ron in dir and checks them against the default locale
+    pub fn load(dir: &str) -> Result<Locales, String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))?;
+
+        let mut locales = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| format!("{}: {}", dir, e))?.path();
+            if path.extension() != Some(OsStr::new("ron")) {
+                continue;
+            }
+
+            let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let mut locale: Locale = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+            locale.code = path.file_stem().unwrap().to_string_lossy().into_owned();
+            locales.push(locale);
+        }
+        locales.sort_by(|a, b| a.code.cmp(&b.code));
+
+        let reference = match locales.iter().position(|l| l.code == DEFAULT_LOCALE) {
+            Some(i) => i,
+            None => return Err(format!("{}: {}.ron is missing", dir, DEFAULT_LOCALE))
+        };
+
+        let mut errors = Vec::new();
+        for locale in &locales {
+            for (key, text) in &locales[reference].strings {
+                match locale.strings.get(key) {
+                    Some(translated) => {
+                        if placeholders(translated) != placeholders(text) {
+                            errors.push(format!("{}: \"{}\" should have the placeholders of {} ({:?})", locale.code, key, DEFAULT_LOCALE, placeholders(text)));
+                        }
+                    },
+                    None => errors.push(format!("{}: \"{}\" is missing", locale.code, key))
+                }
+            }
+            for key in locale.strings.keys() {
+                if !locales[reference].strings.contains_key(key) {
+                    errors.push(format!("{}: \"{}\" isn't in {}", locale.code, key, DEFAULT_LOCALE));
+                }
+            }
+        }
+
+        errors_to_result(dir, errors, Locales{locales, current: reference})
+    }
+
+    // keys a data file refers to, like the hull names in hulls.ron, every
+    // locale has the same keys so one of them is enough to check
+    pub fn check_keys(&self, path: &str, keys: &[&str]) -> Result<(), String> {
+        let errors: Vec<_> = keys.iter()
+            .filter(|key| !self.locales[self.current].strings.contains_key(**key))
+            .map(|key| format!("\"{}\" isn't in the locales", key))
+            .collect();
+
+        errors_to_result(path, errors, ())
+    }
+
+    pub fn select(&mut self, code: &str) -> Result<(), String> {
+        match self.locales.iter().position(|l| l.code == code) {
+            Some(i) => {
+                self.current = i;
+                Ok(())
+            },
+            None => {
+                let codes: Vec<_> = self.locales.iter().map(|l| l.code.as_str()).collect();
+                Err(format!("unknown language \"{}\", expected one of {}", code, codes.join(", ")))
+            }
+        }
+    }
+
+    // cycles through the locales, for the menu button
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % self.locales.len();
+    }
+
+    // of the current locale, in its own language
+    pub fn name(&self) -> &str {
+        &self.locales[self.current].name
+    }
+
+    pub fn tr(&self, key: &str) -> &str {
+        match self.locales[self.current].strings.get(key) {
+            Some(text) => text,
+            None => panic!("unknown string \"{}\", is it in {}.ron?", key, DEFAULT_LOCALE)
+        }
+    }
+
+    // tr with every {name} replaced by its value
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.tr(key).to_owned();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+
+        text
+    }
+}
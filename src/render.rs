@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use sdl2::event::Event;
+use sdl2::image::LoadTexture;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas, RenderTarget, Texture, TextureCreator};
+use sdl2::surface::Surface;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use crate::textures::{Direction, TextureId, TextureRegistry};
+
+// rendered text not drawn for this many frames is thrown away
+const TEXT_CACHE_FRAMES: u64 = 120;
+
+#[derive (Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontSize {
+    NORMAL,
+    SMALL
+}
+
+// the handful of drawing operations the scenes use, so a frame can go to the
+// window or to memory without the scenes knowing which
+pub trait Renderer {
+    // what the scenes lay themselves out in, the logical size when there is
+    // one and pixels otherwise
+    fn output_size(&self) -> (u32, u32);
+    // from where the mouse is in the window to where that is in output_size
+    fn to_logical(&self, x: i32, y: i32) -> (i32, i32);
+    fn registry(&self) -> &TextureRegistry;
+    fn texture_size(&self, id: TextureId, dir: Direction) -> (u32, u32);
+    fn text_size(&self, text: &str, font: FontSize) -> (u32, u32);
+    // the fonts' size comes from the config, layouts go by this instead
+    fn line_height(&self, font: FontSize) -> u32;
+
+    fn clear(&mut self, color: Color);
+    // translucent colors are blended over what is already there
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+    // the texture is stretched to fill rect
+    fn draw_texture(&mut self, id: TextureId, dir: Direction, rect: Rect);
+    // x and y are the top left corner of the text
+    fn draw_text(&mut self, text: &str, font: FontSize, color: Color, x: i32, y: i32);
+    fn present(&mut self);
+
+    // the event with its mouse position moved by to_logical
+    fn logical_event(&self, event: &Event) -> Event {
+        let mut event = event.clone();
+        match event {
+            Event::MouseMotion { ref mut x, ref mut y, .. } |
+            Event::MouseButtonDown { ref mut x, ref mut y, .. } |
+            Event::MouseButtonUp { ref mut x, ref mut y, .. } => {
+                let (logical_x, logical_y) = self.to_logical(*x, *y);
+                *x = logical_x;
+                *y = logical_y;
+            },
+            _ => ()
+        }
+
+        event
+    }
+
+    // the diamond touching the middle of every side of rect, like the ground of a tile
+    fn fill_diamond(&mut self, rect: Rect, color: Color) {
+        let half_height = rect.height() as f32 / 2.0;
+        for row in 0..rect.height() as i32 {
+            let from_middle = (row as f32 + 0.5 - half_height).abs() / half_height;
+            let width = (rect.width() as f32 * (1.0 - from_middle)).round() as u32;
+            if width > 0 {
+                let x = rect.x() + (rect.width() - width) as i32 / 2;
+                self.fill_rect(rect!(x, rect.y() + row, width, 1), color);
+            }
+        }
+    }
+
+    fn anchor(&self, id: TextureId) -> (isize, isize) {
+        self.registry().anchor(id)
+    }
+
+    // for sprites without directions
+    fn named_size(&self, name: &str) -> (u32, u32) {
+        self.texture_size(self.registry().id(name), Direction::NONE)
+    }
+
+    fn draw_named(&mut self, name: &str, rect: Rect) {
+        let id = self.registry().id(name);
+        self.draw_texture(id, Direction::NONE, rect);
+    }
+}
+
+struct CachedText<'a> {
+    texture: Texture<'a>,
+    last_used: u64
+}
+
+// the loaded textures, one per sprite and direction, kept out of the lib
+// so it builds without SDL
+struct Textures<'a> {
+    registry: TextureRegistry,
+    textures: HashMap<(TextureId, Direction), Texture<'a>>
+}
+
+impl<'a> Textures<'a> {
+    fn load<T>(texture_creator: &'a TextureCreator<T>, registry: &TextureRegistry) -> Textures<'a> {
+        let mut textures = HashMap::new();
+        for (id, dir, path) in registry.files() {
+            let texture = match texture_creator.load_texture(&path) {
+                Ok(texture) => texture,
+                Err(e) => panic!("couldn't load {}: {}", path, e)
+            };
+            textures.insert((id, dir), texture);
+        }
+
+        Textures{registry: registry.clone(), textures}
+    }
+
+    fn get(&self, id: TextureId, dir: Direction) -> &Texture<'a> {
+        match self.textures.get(&(id, dir)) {
+            Some(texture) => texture,
+            None => panic!("texture \"{}\" has no {:?} variant", self.registry.name(id), dir)
+        }
+    }
+
+    fn registry(&self) -> &TextureRegistry {
+        &self.registry
+    }
+}
+
+// draws through an SDL canvas, T is Window when playing and Surface when
+// rendering offscreen
+pub struct SdlRenderer<'a, T: RenderTarget> {
+    canvas: Canvas<T>,
+    texture_creator: &'a TextureCreator<T::Context>,
+    textures: Textures<'a>,
+    font: Font<'a, 'static>,
+    small_font: Font<'a, 'static>,
+
+    // text rendered in earlier frames, looked up by font and color then by string
+    text_cache: HashMap<(FontSize, Color), HashMap<String, CachedText<'a>>>,
+    frame_count: u64,
+
+    // pixels per window point, above 1 on high-DPI displays
+    mouse_scale: (f32, f32)
+}
+
+impl<'a, T: RenderTarget> SdlRenderer<'a, T> {
+    pub fn new(canvas: Canvas<T>, texture_creator: &'a TextureCreator<T::Context>, registry: &TextureRegistry,
+               font: Font<'a, 'static>, small_font: Font<'a, 'static>) -> SdlRenderer<'a, T> {
+        let textures = Textures::load(texture_creator, registry);
+        SdlRenderer{canvas, texture_creator, textures, font, small_font, text_cache: HashMap::new(), frame_count: 0, mouse_scale: (1.0, 1.0)}
+    }
+
+    fn font(&self, font: FontSize) -> &Font<'a, 'static> {
+        match font {
+            FontSize::NORMAL => &self.font,
+            FontSize::SMALL => &self.small_font
+        }
+    }
+}
+
+impl<'a> SdlRenderer<'a, Window> {
+    // has to be called when the window changes size, SDL already moves the
+    // mouse into logical coordinates when a logical size is set
+    pub fn resized(&mut self) {
+        let (window_width, window_height) = self.canvas.window().size();
+        let (pixel_width, pixel_height) = self.canvas.output_size().unwrap();
+
+        self.mouse_scale =
+            if self.canvas.logical_size() != (0, 0) || window_width == 0 || window_height == 0 {
+                (1.0, 1.0)
+            }
+            else {
+                (pixel_width as f32 / window_width as f32, pixel_height as f32 / window_height as f32)
+            };
+    }
+}
+
+// a software canvas drawing into a plain RGBA surface, no window or GPU needed
+pub fn software_canvas(width: u32, height: u32) -> Result<Canvas<Surface<'static>>, String> {
+    Surface::new(width, height, PixelFormatEnum::RGBA8888)?.into_canvas()
+}
+
+impl<'a> SdlRenderer<'a, Surface<'static>> {
+    // copy of what has been drawn so far
+    pub fn frame(&self) -> Result<Surface<'static>, String> {
+        let surface = self.canvas.surface();
+        surface.convert(&surface.pixel_format())
+    }
+}
+
+impl<'a, T: RenderTarget> Renderer for SdlRenderer<'a, T> {
+    fn output_size(&self) -> (u32, u32) {
+        match self.canvas.logical_size() {
+            (0, 0) => self.canvas.output_size().unwrap(),
+            size => size
+        }
+    }
+
+    fn to_logical(&self, x: i32, y: i32) -> (i32, i32) {
+        ((x as f32 * self.mouse_scale.0) as i32, (y as f32 * self.mouse_scale.1) as i32)
+    }
+
+    fn registry(&self) -> &TextureRegistry {
+        self.textures.registry()
+    }
+
+    fn texture_size(&self, id: TextureId, dir: Direction) -> (u32, u32) {
+        let info = self.textures.get(id, dir).query();
+        (info.width, info.height)
+    }
+
+    fn text_size(&self, text: &str, font: FontSize) -> (u32, u32) {
+        self.font(font).size_of(text).unwrap()
+    }
+
+    fn line_height(&self, font: FontSize) -> u32 {
+        self.font(font).height() as u32
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.canvas.set_draw_color(color);
+        self.canvas.clear();
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.canvas.set_blend_mode(BlendMode::Blend);
+        self.canvas.set_draw_color(color);
+        self.canvas.fill_rect(rect).unwrap();
+        self.canvas.set_blend_mode(BlendMode::None);
+    }
+
+    fn draw_texture(&mut self, id: TextureId, dir: Direction, rect: Rect) {
+        self.canvas.copy(self.textures.get(id, dir), None, rect).unwrap();
+    }
+
+    fn draw_text(&mut self, text: &str, font: FontSize, color: Color, x: i32, y: i32) {
+        if text.is_empty() {
+            return;
+        }
+
+        let cached = match self.text_cache.get(&(font, color)) {
+            Some(texts) => texts.contains_key(text),
+            None => false
+        };
+        if !cached {
+            let font_s = self.font(font).render(text).blended(color).unwrap();
+            let texture = self.texture_creator.create_texture_from_surface(&font_s).unwrap();
+            self.text_cache.entry((font, color)).or_default().insert(text.to_owned(), CachedText{texture, last_used: 0});
+        }
+
+        let entry = self.text_cache.get_mut(&(font, color)).unwrap().get_mut(text).unwrap();
+        entry.last_used = self.frame_count;
+        let info = entry.texture.query();
+        self.canvas.copy(&entry.texture, None, rect!(x, y, info.width, info.height)).unwrap();
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+
+        let frame = self.frame_count;
+        for texts in self.text_cache.values_mut() {
+            texts.retain(|_, text| frame - text.last_used < TEXT_CACHE_FRAMES);
+        }
+        self.text_cache.retain(|_, texts| !texts.is_empty());
+        self.frame_count += 1;
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::input::TickInput;
+use crate::island::Island;
+
+// bumped whenever the format changes, or the saves and actions in it mean
+// something else, older replays are refused
+const REPLAY_VERSION: u32 = 4;
+
+// a whole session, the seed it started from and the input of every tick. it
+// only plays back the same with the same data files (manifest, enemies, hulls, balance)
+#[derive (Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    pub seed: u64,
+    pub start_island: Option<Island>,
+
+    // ticks without input are left out
+    inputs: Vec<(u64, TickInput)>,
+    ticks: u64
+}
+
+// where the last session is recorded to unless --record says otherwise
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("pocket-pirates").join("last_replay.ron"))
+}
+
+impl Replay {
+    pub fn new(seed: u64, start_island: Option<Island>) -> Replay {
+        Replay{version: REPLAY_VERSION, seed, start_island, inputs: Vec::new(), ticks: 0}
+    }
+
+    pub fn load(path: &str) -> Result<Replay, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let replay: Replay = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(format!("{}: replay version {}, expected {}", path, replay.version, REPLAY_VERSION));
+        }
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = ron::ser::to_string(self).map_err(|e| e.to_string())?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    // input of the tick that's about to run
+    pub fn record(&mut self, input: &TickInput) {
+        if !input.is_empty() {
+            self.inputs.push((self.ticks, input.clone()));
+        }
+        self.ticks += 1;
+    }
+}
+
+// feeds a replay back one tick at a time
+pub struct Playback {
+    replay: Replay,
+    tick: u64,
+    next: usize
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Playback {
+        Playback{replay, tick: 0, next: 0}
+    }
+
+    // None once every recorded tick has been played
+    pub fn next_tick(&mut self) -> Option<TickInput> {
+        if self.tick >= self.replay.ticks {
+            return None;
+        }
+
+        let mut input = TickInput::default();
+        if let Some((tick, recorded)) = self.replay.inputs.get(self.next) {
+            if *tick == self.tick {
+                input = recorded.clone();
+                self.next += 1;
+            }
+        }
+        self.tick += 1;
+
+        Some(input)
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::PathBuf;
+
+use rand::Rng;
+
+use crate::{Boat, GameState, rng_from_seed};
+use crate::island::Island;
+
+pub const SAVE_SLOTS: usize = 3;
+
+// bumped whenever the format changes, older saves are refused
+const SAVE_VERSION: u32 = 3;
+
+// everything needed to pick a run back up, the data files (manifest, enemies,
+// hulls, balance) are loaded fresh instead
+#[derive (Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    version: u32,
+    // sprite names in manifest order when saved, texture ids are looked up again on load
+    textures: Vec<String>,
+
+    seed: u64,
+    // the rng can't be stored, so it's reseeded from this on save and on load
+    rng_seed: u64,
+
+    player_boat: Boat,
+    enemy_boat: Boat,
+    island: Island,
+    start_island: Option<Island>
+}
+
+fn slot_path(slot: usize) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("pocket-pirates").join(format!("slot{}.ron", slot + 1)))
+}
+
+pub fn exists(slot: usize) -> bool {
+    match slot_path(slot) {
+        Some(path) => path.is_file(),
+        None => false
+    }
+}
+
+pub fn save(state: &mut GameState, slot: usize) -> Result<(), String> {
+    let path = slot_path(slot).ok_or("no data directory to save in")?;
+
+    let rng_seed = state.rng.gen::<u64>();
+    state.rng = rng_from_seed(rng_seed);
+
+    // a replay still has to reseed like the recorded session did
+    if state.replaying {
+        return Ok(());
+    }
+
+    let save = SaveGame{version: SAVE_VERSION, textures: state.textures.names(), seed: state.seed, rng_seed,
+                        player_boat: state.player_boat.clone(), enemy_boat: state.enemy_boat.clone(),
+                        island: state.island.clone(), start_island: state.start_island.clone()};
+    let text = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()).map_err(|e| e.to_string())?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn read(slot: usize) -> Result<SaveGame, String> {
+    let path = slot_path(slot).ok_or("no data directory to load from")?;
+    let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let save: SaveGame = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if save.version != SAVE_VERSION {
+        return Err(format!("{}: save version {}, expected {}", path.display(), save.version, SAVE_VERSION));
+    }
+
+    Ok(save)
+}
+
+// puts a save read from the slot into the game state
+pub fn restore(state: &mut GameState, mut save: SaveGame, slot: usize) -> Result<(), String> {
+    // the manifest may have changed since, go through the names
+    {
+        let textures = &state.textures;
+        let names = &save.textures;
+        let mut objects: Vec<_> = save.island.objects.values_mut().collect();
+        if let Some(island) = save.start_island.as_mut() {
+            objects.extend(island.objects.values_mut());
+        }
+        objects.extend(save.player_boat.obj.as_mut());
+        objects.extend(save.enemy_boat.obj.as_mut());
+        for obj in objects {
+            obj.texture = textures.remap(obj.texture, names).map_err(|e| format!("save slot {}: {}", slot + 1, e))?;
+        }
+    }
+
+    state.seed = save.seed;
+    state.rng = rng_from_seed(save.rng_seed);
+    state.player_boat = save.player_boat;
+    state.enemy_boat = save.enemy_boat;
+    state.island = save.island;
+    state.start_island = save.start_island;
+    state.slot = Some(slot);
+
+    Ok(())
+}
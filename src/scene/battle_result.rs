@@ -0,0 +1,153 @@
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use crate::{GameState, BATTLE_RESULT_BG_WIDTH, BATTLE_RESULT_BG_HEIGHT,
+            BATTLE_RESULT_BG_COLOR, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT, ACTION_HUD_BORDER};
+use crate::save;
+use crate::input::{Action, TickInput};
+use crate::inventory::ResourceKind;
+use crate::render::{FontSize, Renderer};
+use crate::scene::{Scene, Transition, draw_button};
+use crate::textures::Direction;
+
+// (left, top, middle_x) of the result panel
+fn panel_layout(renderer: &dyn Renderer) -> (u32, u32, u32) {
+    let (w_width, w_height) = renderer.output_size();
+    (w_width.saturating_sub(BATTLE_RESULT_BG_WIDTH) / 2, w_height.saturating_sub(BATTLE_RESULT_BG_HEIGHT) / 2, w_width / 2)
+}
+
+// shown over the combat scene once the enemy sinks: keep or steal the boat,
+// then repair it, then pop back into combat against the next enemy
+pub struct BattleResultScene {
+    option: i32
+}
+
+impl BattleResultScene {
+    pub fn new() -> BattleResultScene {
+        BattleResultScene{option: 0}
+    }
+}
+
+impl Scene for BattleResultScene {
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        let (left, top, _middle_x) = panel_layout(renderer);
+
+        match *event {
+            Event::MouseButtonUp { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } => {
+                let rect1 = rect!(left + ACTION_HUD_BORDER, top + ACTION_HUD_BORDER + 40, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT);
+                let rect2 = rect!(left + ACTION_HUD_BORDER * 2 + BATTLE_RESULT_BUTTON_WIDTH, top + ACTION_HUD_BORDER + 40,
+                                  BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT);
+                if x >= rect1.x && x <= rect1.x + rect1.w && y >= rect1.y && y <= rect1.y + rect1.h {
+                    Some(Action::CHOICE(0))
+                } else if x >= rect2.x && x <= rect2.x + rect2.w && y >= rect2.y && y <= rect2.y + rect2.h {
+                    Some(Action::CHOICE(1))
+                } else {
+                    None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        for action in &input.actions {
+            let choice = match *action {
+                Action::CHOICE(choice) => choice,
+                _ => continue
+            };
+
+            let player_boat = &mut state.player_boat;
+            let enemy_boat = &mut state.enemy_boat;
+            let balance = &state.balance;
+
+            if self.option == 0 {
+                if choice == 0 {
+                    self.option += 1;
+                } else {
+                    player_boat.health = (enemy_boat.max_health as f32 / 2.0).ceil() as isize;
+                    player_boat.shield = 0;
+                    player_boat.max_health = enemy_boat.max_health;
+                    player_boat.obj.as_mut().unwrap().texture = enemy_boat.obj.unwrap().texture;
+                    player_boat.obj.as_mut().unwrap().dir = Direction::SE;
+                    player_boat.attacks = enemy_boat.attacks.clone();
+                    player_boat.parts = enemy_boat.parts.clone();
+                    self.option += 1;
+                }
+            } else if self.option == 1 {
+                if choice == 0 {
+                    let mut health_to_buy = player_boat.inventory.get(ResourceKind::WOOD) / balance.repair_wood_per_health;
+                    let shield_to_buy = player_boat.inventory.get(ResourceKind::MINERAL) / balance.repair_mineral_per_shield;
+                    if health_to_buy > player_boat.max_health - player_boat.health {
+                        health_to_buy = player_boat.max_health - player_boat.health;
+                    }
+                    player_boat.health += health_to_buy;
+                    player_boat.shield += shield_to_buy;
+                    player_boat.inventory.take(ResourceKind::WOOD, health_to_buy * balance.repair_wood_per_health);
+                    player_boat.inventory.take(ResourceKind::MINERAL, shield_to_buy * balance.repair_mineral_per_shield);
+                    self.option += 1;
+                } else {
+                    self.option += 1;
+                }
+            }
+            if self.option == 2 { // next battle
+                player_boat.enabled_attacks = player_boat.attacks.clone();
+                player_boat.enabled_parts = player_boat.parts.clone();
+                player_boat.can_attack = 0;
+
+                *enemy_boat = state.enemies.spawn(&state.textures, &mut state.rng);
+
+                if let Some(slot) = state.slot {
+                    if let Err(e) = save::save(state, slot) {
+                        eprintln!("autosave failed: {}", e);
+                    }
+                }
+                return Transition::POP;
+            }
+        }
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, _alpha: f32) {
+        let (left, top, middle_x) = panel_layout(renderer);
+        let player_boat = &state.player_boat;
+        let enemy_boat = &state.enemy_boat;
+
+        // background
+        let rect = rect!(left, top, BATTLE_RESULT_BG_WIDTH, BATTLE_RESULT_BG_HEIGHT);
+        renderer.fill_rect(rect, BATTLE_RESULT_BG_COLOR);
+
+        // top message
+        {
+            let txt =
+                if enemy_boat.inventory.is_empty() {
+                    state.locales.tr("no_loot").to_owned()
+                }
+                else {
+                    let loot: Vec<_> = enemy_boat.inventory.iter()
+                        .map(|(kind, amount)| state.locales.format("resource_amount", &[("amount", &amount), ("resource", &state.locales.tr(kind.name()))]))
+                        .collect();
+                    state.locales.format("loot", &[("loot", &loot.join(", "))])
+                };
+            let (text_width, _text_height) = renderer.text_size(&txt, FontSize::SMALL);
+            renderer.draw_text(&txt, FontSize::SMALL, Color::RGBA(255, 255, 255, 255), middle_x as i32 - text_width as i32 / 2, top as i32);
+        }
+
+        let rect1 = rect!(left + ACTION_HUD_BORDER, top + ACTION_HUD_BORDER + 40, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT);
+        let rect2 = rect!(left + ACTION_HUD_BORDER * 2 + BATTLE_RESULT_BUTTON_WIDTH, top + ACTION_HUD_BORDER + 40, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT);
+
+        // choose boat buttons
+        if self.option == 0 {
+            draw_button(renderer, rect1, state.locales.tr("keep_boat"));
+            draw_button(renderer, rect2, state.locales.tr("steal_boat"));
+        } else if self.option == 1 {
+            let cost = (player_boat.max_health - player_boat.health) * state.balance.repair_wood_per_health;
+            draw_button(renderer, rect1, &state.locales.format("repair_boat", &[("cost", &cost)]));
+            draw_button(renderer, rect2, state.locales.tr("skip_repair"));
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
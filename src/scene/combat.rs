@@ -0,0 +1,415 @@
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use crate::{Boat, GameState, Object, AttackType, Target, BG_COLOR, UI_BG_COLOR,
+            TILE_WIDTH, TILE_HEIGHT, ACTION_HUD_BORDER, ACTION_HUD_WIDTH, ACTION_HUD_HEIGHT, ACTION_HUD_BUTTON_WIDTH,
+            ACTION_HUD_BUTTON_HEIGHT, BOAT_PLAYER_COMBAT_X, BOAT_PLAYER_COMBAT_Y, LIFE_BAR_X, LIFE_BAR_Y, LIFE_BAR_ICON_SCALE};
+use crate::combat::{self, Command, Outcome, TurnReport};
+use crate::island::WATER;
+use crate::locale::Locales;
+use crate::input::{Action, TickInput};
+use crate::render::{FontSize, Renderer};
+use crate::camera::{View, iso_to_world};
+use crate::scene::{Scene, Transition, tile_texture, draw_button, draw_materials_hud};
+use crate::scene::battle_result::BattleResultScene;
+use crate::scene::game_over::GameOverScene;
+
+// ticks the "missed" text stays on screen after a missed shot
+const MISS_TEXT_FRAMES: i32 = 12;
+
+#[derive (Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ButtonType {
+    NONE,
+    ATTACK,
+    HARPOON,
+    NET,
+    CANNON1,
+    CANNON2,
+    HELM,
+    POLE
+}
+
+struct Button {
+    // string key, see assets/locales
+    label : &'static str,
+    enabled : bool,
+    typ : ButtonType
+}
+
+// the four buttons sit in a 2x2 grid at the bottom left
+fn button_rect(i : usize, w_height : u32) -> sdl2::rect::Rect {
+    let (column, row) = ((i % 2) as u32, (i / 2) as u32);
+    rect!(ACTION_HUD_BORDER * (2 + column) + ACTION_HUD_BUTTON_WIDTH * column, w_height.saturating_sub(ACTION_HUD_HEIGHT) + (ACTION_HUD_BORDER + ACTION_HUD_BUTTON_HEIGHT) * row,
+          ACTION_HUD_BUTTON_WIDTH, ACTION_HUD_BUTTON_HEIGHT)
+}
+
+fn update_menu_with_abilities(player_boat : &Boat, cur_buttons : &mut [Button]) {
+    let mut i = 1;
+    for atk in &player_boat.enabled_attacks {
+        if *atk == AttackType::NORMAL {
+            continue;
+        }
+
+        match atk {
+            AttackType::HARPOON => {
+                cur_buttons[i].enabled = true;
+                cur_buttons[i].typ = ButtonType::HARPOON;
+                cur_buttons[i].label = "harpoon";
+                i += 1;
+                continue;
+            },
+            AttackType::NET => {
+                cur_buttons[i].enabled = true;
+                cur_buttons[i].typ = ButtonType::NET;
+                cur_buttons[i].label = "net";
+                i += 1;
+                continue;
+            },
+            _ => ()
+        }
+    }
+}
+
+fn revive_menu(player_boat : &Boat, cur_buttons : &mut [Button]) {
+    cur_buttons[0].enabled = true;
+    cur_buttons[0].label = "attack";
+    cur_buttons[0].typ = ButtonType::ATTACK;
+    cur_buttons[1].enabled = false;
+    cur_buttons[2].enabled = false;
+    cur_buttons[3].enabled = false;
+    update_menu_with_abilities(player_boat, cur_buttons);
+}
+
+// from and to are in world pixels
+fn draw_shot(renderer: &mut dyn Renderer, view: &View, attack: AttackType, from: (isize, isize), to: (isize, isize), progress: f32, second_ball_dy: Option<isize>) {
+    let (ball_width, ball_height) = renderer.named_size("ball");
+
+    let x = ((to.0 - from.0) as f32 * progress) as isize + from.0 + 50;
+    let y = ((to.1 - from.1) as f32 * progress) as isize + from.1;
+
+    match attack {
+        AttackType::HARPOON => {
+            let rect = view.rect(x, y + 50, ball_width, ball_height);
+            renderer.draw_named("gameover", rect);
+        },
+
+        AttackType::NET => {
+            let rect = view.rect(x, y + 50, ball_width, ball_height);
+            renderer.draw_named("grass", rect);
+        },
+
+        AttackType::NORMAL => {
+            let rect = view.rect(x, y + 50, ball_width, ball_height);
+            renderer.draw_named("ball", rect);
+
+            if let Some(dy) = second_ball_dy {
+                let rect = view.rect(x, y + dy, ball_width, ball_height);
+                renderer.draw_named("ball", rect);
+            }
+        }
+    }
+}
+
+fn draw_systems_hud(renderer: &mut dyn Renderer, locales: &Locales, boat: &Boat, bg_rect: sdl2::rect::Rect, text_x: i32, text_y: f32) {
+    renderer.fill_rect(bg_rect, UI_BG_COLOR);
+
+    let systems = [(Target::HELM, "helm"), (Target::POLE, "pole"), (Target::CANNON1, "cannon1"), (Target::CANNON2, "cannon2")];
+    for (row, (part, name)) in systems.iter().enumerate() {
+        if !boat.parts.contains(part) {
+            continue;
+        }
+
+        let (text, color) =
+            if boat.enabled_parts.contains(part) {
+                (locales.format("part_ok", &[("part", &locales.tr(name))]), Color::RGBA(255, 255, 255, 255))
+            }
+            else {
+                (locales.format("part_destroyed", &[("part", &locales.tr(name))]), Color::RGBA(255, 55, 55, 255))
+            };
+
+        let (_text_width, text_height) = renderer.text_size(&text, FontSize::NORMAL);
+        renderer.draw_text(&text, FontSize::NORMAL, color, text_x, (text_y + (row as u32 * text_height) as f32) as i32);
+    }
+}
+
+pub struct CombatScene {
+    map: [[usize; 30]; 30],
+    cur_buttons: Vec<Button>,
+
+    turn: Option<TurnReport>,
+    animation_timer: i32,
+    animation_start_timer: i32,
+    miss_timer: i32,
+
+    enemy_defeated: i32
+}
+
+impl CombatScene {
+    pub fn new(player_boat : &Boat) -> CombatScene {
+        let mut cur_buttons = vec!(
+            Button{label: "attack", enabled: true, typ: ButtonType::ATTACK},
+            Button{label: "", enabled: false, typ: ButtonType::NONE},
+            Button{label: "", enabled: false, typ: ButtonType::NONE},
+            Button{label: "", enabled: false, typ: ButtonType::NONE}
+            );
+        update_menu_with_abilities(player_boat, &mut cur_buttons);
+
+        CombatScene{map: [[WATER; 30]; 30], cur_buttons, turn: None, animation_timer: 0, animation_start_timer: 0, miss_timer: 0, enemy_defeated: 0}
+    }
+}
+
+impl Scene for CombatScene {
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        let (_w_width, w_height) = renderer.output_size();
+
+        match *event {
+            Event::MouseButtonUp { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } => {
+                for (i, button) in self.cur_buttons.iter().enumerate() {
+                    let r = button_rect(i, w_height);
+                    if button.enabled && x >= r.x && x <= r.x + r.w && y >= r.y && y <= r.y + r.h {
+                        return Some(Action::BUTTON(button.typ));
+                    }
+                }
+                None
+            },
+
+            _ => None
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        let cur_buttons = &mut self.cur_buttons;
+
+        for action in &input.actions {
+            let typ = match *action {
+                Action::BUTTON(typ) => typ,
+                _ => continue
+            };
+            // the menu may have changed between the click and this tick
+            if !cur_buttons.iter().any(|button| button.enabled && button.typ == typ) {
+                continue;
+            }
+
+            let command = match typ {
+                ButtonType::ATTACK => {
+                    // TODO: depending on the enemy, the number of cannons may vary
+                    cur_buttons[0].enabled = state.enemy_boat.parts.contains(&Target::POLE);
+                    cur_buttons[0].typ = ButtonType::POLE;
+                    cur_buttons[0].label = "pole";
+                    cur_buttons[1].enabled = state.enemy_boat.parts.contains(&Target::HELM);
+                    cur_buttons[1].typ = ButtonType::HELM;
+                    cur_buttons[1].label = "helm";
+                    cur_buttons[2].enabled = state.enemy_boat.parts.contains(&Target::CANNON1);
+                    cur_buttons[2].typ = ButtonType::CANNON1;
+                    cur_buttons[2].label = "cannon1";
+                    cur_buttons[3].enabled = state.enemy_boat.parts.contains(&Target::CANNON2);
+                    cur_buttons[3].typ = ButtonType::CANNON2;
+                    cur_buttons[3].label = "cannon2";
+
+                    None
+                },
+                ButtonType::HARPOON => Some(Command{attack: AttackType::HARPOON, target: Target::NONE}),
+                ButtonType::NET => Some(Command{attack: AttackType::NET, target: Target::NONE}),
+                ButtonType::POLE => Some(Command{attack: AttackType::NORMAL, target: Target::POLE}),
+                ButtonType::HELM => Some(Command{attack: AttackType::NORMAL, target: Target::HELM}),
+                ButtonType::CANNON1 => Some(Command{attack: AttackType::NORMAL, target: Target::CANNON1}),
+                ButtonType::CANNON2 => Some(Command{attack: AttackType::NORMAL, target: Target::CANNON2}),
+                _ => None
+            };
+
+            if let Some(command) = command {
+                let enemy_command = combat::choose_enemy_command(&state.player_boat, &state.enemy_boat, &mut state.rng);
+                self.turn = Some(combat::resolve_turn(&mut state.player_boat, &mut state.enemy_boat, command, enemy_command, &state.balance, &mut state.rng));
+
+                self.animation_timer = 20;
+                self.animation_start_timer = 20;
+
+                cur_buttons[0].enabled = false;
+                cur_buttons[1].enabled = false;
+                cur_buttons[2].enabled = false;
+                cur_buttons[3].enabled = false;
+            }
+        }
+
+        if self.miss_timer > 0 {
+            self.miss_timer -= 1;
+        }
+
+        if self.enemy_defeated >= 0 {
+            self.enemy_defeated -= 1;
+            if self.enemy_defeated == 0 {
+                return Transition::PUSH(Box::new(BattleResultScene::new()));
+            }
+        }
+
+        if self.animation_timer > 0 {
+            self.animation_timer -= 1;
+
+            // damage
+            if self.animation_timer == 0 {
+                let turn = self.turn.unwrap();
+
+                if turn.player_outcome == Outcome::MISSED || turn.enemy_outcome == Outcome::MISSED {
+                    self.miss_timer = MISS_TEXT_FRAMES;
+                }
+
+                if turn.player_sunk {
+                    return Transition::REPLACE(Box::new(GameOverScene::new()));
+                }
+
+                // a sunk enemy's menu only comes back on resume, after the battle
+                // result, a click before that would fire at the wreck and loot it again
+                if turn.enemy_sunk {
+                    self.enemy_defeated = 3;
+                }
+                else {
+                    revive_menu(&state.player_boat, &mut self.cur_buttons);
+                }
+            }
+        }
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, alpha: f32) {
+        let (w_width, w_height) = renderer.output_size();
+        let player_boat = &state.player_boat;
+        let enemy_boat = &state.enemy_boat;
+
+        renderer.clear(BG_COLOR);
+
+        // the player boat keeps its island position, it's only moved out to sea here
+        let player_obj = Object{x: BOAT_PLAYER_COMBAT_X, y: BOAT_PLAYER_COMBAT_Y, ..player_boat.obj.unwrap()};
+        let enemy_obj = enemy_boat.obj.unwrap();
+
+        // in world pixels
+        let (player_x, player_y) = iso_to_world(player_obj.x, player_obj.y);
+        let player_anchor = renderer.anchor(player_obj.texture);
+        let (player_x, player_y) = (player_x + player_anchor.0, player_y + player_anchor.1);
+        let (enemy_x, enemy_y) = iso_to_world(enemy_obj.x, enemy_obj.y);
+        let enemy_anchor = renderer.anchor(enemy_obj.texture);
+        let (enemy_x, enemy_y) = (enemy_x + enemy_anchor.0, enemy_y + enemy_anchor.1);
+
+        // both boats with some sea around them, above the actions HUD
+        let view = {
+            let (player_width, player_height) = renderer.texture_size(player_obj.texture, player_obj.dir);
+            let (enemy_width, enemy_height) = renderer.texture_size(enemy_obj.texture, enemy_obj.dir);
+            let boats = rect!(player_x, player_y, player_width, player_height).union(rect!(enemy_x, enemy_y, enemy_width, enemy_height));
+            let sea = rect!(boats.x() - TILE_WIDTH as i32, boats.y() - TILE_HEIGHT as i32,
+                            boats.width() + 2 * TILE_WIDTH as u32, boats.height() + 2 * TILE_HEIGHT as u32);
+            View::framing(sea, rect!(0, 0, w_width, w_height.saturating_sub(ACTION_HUD_HEIGHT + ACTION_HUD_BORDER)))
+        };
+
+        for y in 0..self.map.len() as isize {
+            for x in 0..self.map.len() as isize {
+                let rect = view.tile_rect(x, y, (0, 0), (TILE_WIDTH as u32, TILE_HEIGHT as u32));
+                renderer.draw_named(tile_texture(self.map[x as usize][y as usize]), rect);
+            }
+        }
+
+        // draw boats
+        {
+            // player boat
+            {
+                let (tex_width, tex_height) = renderer.texture_size(player_obj.texture, player_obj.dir);
+                let rect = view.rect(player_x, player_y, tex_width, tex_height);
+                renderer.draw_texture(player_obj.texture, player_obj.dir, rect);
+            }
+
+            // enemy boat
+            {
+                let (tex_width, tex_height) = renderer.texture_size(enemy_obj.texture, enemy_obj.dir);
+                let rect = view.rect(enemy_x, enemy_y, tex_width, tex_height);
+                renderer.draw_texture(enemy_obj.texture, enemy_obj.dir, rect);
+            }
+        }
+
+        // draw actions HUD
+        {
+            // background
+            let rect = rect!(ACTION_HUD_BORDER, w_height.saturating_sub(ACTION_HUD_HEIGHT + ACTION_HUD_BORDER), ACTION_HUD_WIDTH, ACTION_HUD_HEIGHT);
+            renderer.fill_rect(rect, UI_BG_COLOR);
+
+            // buttons
+            for (i, button) in self.cur_buttons.iter().enumerate() {
+                if button.enabled {
+                    draw_button(renderer, button_rect(i, w_height), state.locales.tr(button.label));
+                }
+            }
+
+            // life bar
+            for i in 0..player_boat.health {
+                let (tex_width, tex_height) = renderer.named_size("steerwheel");
+                let rect = rect!((LIFE_BAR_X + (tex_width as f32 * LIFE_BAR_ICON_SCALE) as isize) * i + LIFE_BAR_X, LIFE_BAR_Y,
+                                  LIFE_BAR_ICON_SCALE * tex_width as f32, LIFE_BAR_ICON_SCALE * tex_height as f32);
+                renderer.draw_named("steerwheel", rect);
+            }
+            for i in player_boat.health..player_boat.max_health {
+                let (tex_width, tex_height) = renderer.named_size("steerwheel_dark");
+                let rect = rect!((LIFE_BAR_X + (tex_width as f32 * LIFE_BAR_ICON_SCALE) as isize) * i + LIFE_BAR_X, LIFE_BAR_Y,
+                                  LIFE_BAR_ICON_SCALE * tex_width as f32, LIFE_BAR_ICON_SCALE * tex_height as f32);
+                renderer.draw_named("steerwheel_dark", rect);
+            }
+            for i in 0..player_boat.shield {
+                let (tex_width, tex_height) = renderer.named_size("steerwheel_silver");
+                let rect = rect!((LIFE_BAR_X + (tex_width as f32 * LIFE_BAR_ICON_SCALE) as isize) * i + LIFE_BAR_X, 35 + LIFE_BAR_Y,
+                                  LIFE_BAR_ICON_SCALE * tex_width as f32, LIFE_BAR_ICON_SCALE * tex_height as f32);
+                renderer.draw_named("steerwheel_silver", rect);
+            }
+        }
+
+        // draw systems HUD
+        {
+            let shield_height = LIFE_BAR_ICON_SCALE * renderer.named_size("steerwheel_silver").1 as f32;
+            let line_height = renderer.line_height(FontSize::NORMAL);
+
+            // player
+            let rect = rect!(5, 50.0 + shield_height, 400, 4 * line_height + 5);
+            draw_systems_hud(renderer, &state.locales, player_boat, rect, 8, 47.0 + shield_height);
+
+            //enemy
+            let rect = rect!(w_width as i32 - 400 - 5, 310.0 + shield_height, 400, 4 * line_height + 5);
+            draw_systems_hud(renderer, &state.locales, enemy_boat, rect, w_width as i32 - 400 + 8, 307.0 + shield_height);
+        }
+
+        draw_materials_hud(renderer, player_boat, &state.balance);
+
+        if self.animation_timer > 0 {
+            let turn = self.turn.unwrap();
+            let progress = ((self.animation_start_timer - self.animation_timer) as f32 + alpha) / self.animation_start_timer as f32;
+
+            // TODO: account for different types of attack
+
+            // player attack
+            if turn.player_outcome.fired() {
+                let second_ball = if player_boat.parts.contains(&Target::CANNON2) { Some(70) } else { None };
+                draw_shot(renderer, &view, turn.player_command.attack, (player_x, player_y), (enemy_x, enemy_y), progress, second_ball);
+            }
+
+            // enemy attack
+            if turn.enemy_outcome.fired() {
+                let second_ball = if enemy_boat.parts.contains(&Target::CANNON2) { Some(30) } else { None };
+                draw_shot(renderer, &view, turn.enemy_command.attack, (enemy_x, enemy_y), (player_x, player_y), progress, second_ball);
+            }
+        }
+
+        if self.miss_timer > 0 {
+            let turn = self.turn.unwrap();
+
+            if turn.player_outcome == Outcome::MISSED {
+                let (x, y) = view.to_screen(enemy_x + 10, enemy_y + 100);
+                renderer.draw_text(state.locales.tr("missed"), FontSize::NORMAL, Color::RGBA(255, 255, 255, 255), x as i32, y as i32);
+            }
+
+            if turn.enemy_outcome == Outcome::MISSED {
+                let (x, y) = view.to_screen(player_x + 10, player_y + 100);
+                renderer.draw_text(state.locales.tr("missed"), FontSize::NORMAL, Color::RGBA(255, 255, 255, 255), x as i32, y as i32);
+            }
+        }
+    }
+
+    fn resume(&mut self, state: &mut GameState) {
+        // the battle result may have swapped our boat for the enemy's
+        revive_menu(&state.player_boat, &mut self.cur_buttons);
+    }
+}
@@ -0,0 +1,71 @@
+use rand::prelude::*;
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+
+use crate::{GameState, BG_COLOR};
+use crate::input::{Action, TickInput};
+use crate::render::{FontSize, Renderer};
+use crate::scene::{Scene, Transition};
+
+pub struct GameOverScene {}
+
+impl GameOverScene {
+    pub fn new() -> GameOverScene {
+        GameOverScene{}
+    }
+}
+
+impl Scene for GameOverScene {
+    fn input(&mut self, event: &Event, _renderer: &dyn Renderer) -> Option<Action> {
+        match *event {
+            // start over on the island with a fresh seed
+            Event::KeyUp { keycode: Some(Keycode::Return), .. } => Some(Action::RESTART(random::<u64>())),
+            Event::KeyUp { keycode: Some(Keycode::Escape), .. } => Some(Action::QUIT),
+            _ => None
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        for action in &input.actions {
+            match *action {
+                Action::RESTART(seed) => {
+                    println!("seed: {}", seed);
+                    // only fails when no island fits the data files, which a new seed won't fix
+                    match state.restart(seed) {
+                        Ok(new_state) => *state = new_state,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            return Transition::QUIT;
+                        }
+                    }
+
+                    return Transition::POP;
+                },
+                Action::QUIT => return Transition::QUIT,
+                _ => ()
+            }
+        }
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, _alpha: f32) {
+        let (w_width, w_height) = renderer.output_size();
+
+        renderer.clear(BG_COLOR);
+
+        let (tex_width, tex_height) = renderer.named_size("gameover");
+        let rect = rect!(w_width.saturating_sub(tex_width / 2) / 2, w_height.saturating_sub(tex_height / 2) / 2, tex_width / 2, tex_height / 2);
+        renderer.draw_named("gameover", rect);
+
+        // seed, so the run can be reproduced
+        let mut y = rect.y + rect.h + 10;
+        for txt in &[state.locales.format("seed", &[("seed", &state.seed)]), state.locales.tr("game_over_help").to_owned()] {
+            let (text_width, text_height) = renderer.text_size(txt, FontSize::NORMAL);
+            renderer.draw_text(txt, FontSize::NORMAL, Color::RGBA(255, 255, 255, 255), (w_width as i32 - text_width as i32) / 2, y);
+            y += text_height as i32;
+        }
+    }
+}
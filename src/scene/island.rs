@@ -0,0 +1,254 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use std::collections::VecDeque;
+
+use sdl2::rect::Rect;
+
+use crate::{GameState, Object, BG_COLOR, HOVER_COLOR, UI_BG_COLOR, MINING_BAR_COLOR, MINING_BAR_HEIGHT, MOORED_BOAT_MAX_WIDTH, TILE_WIDTH, TILE_HEIGHT, TILE_GROUND, HALF_TILE_WIDTH, HALF_TILE_HEIGHT,
+            TICKS_PER_SECOND};
+use crate::camera::{Camera, View, map_bounds};
+use crate::island::{Island, GrowthSprites, WATER, tile_at, step_delta, find_path, gather_resource, plant_sapling};
+use crate::textures::{Direction, TextureRegistry};
+use crate::input::{Action, TickInput};
+use crate::render::Renderer;
+use crate::scene::{Scene, Transition, tile_texture, draw_materials_hud};
+use crate::scene::splash::SplashScene;
+use crate::scene::combat::CombatScene;
+use crate::scene::shipyard::ShipyardScene;
+
+// anchor and size of a sprite on the island, the boat is shrunk to fit the dock
+fn placement(renderer: &dyn Renderer, obj: &Object, moored: bool) -> ((isize, isize), (u32, u32)) {
+    let (tex_width, tex_height) = renderer.texture_size(obj.texture, obj.dir);
+    let anchor = renderer.anchor(obj.texture);
+    if !moored || tex_width as isize <= MOORED_BOAT_MAX_WIDTH {
+        return (anchor, (tex_width, tex_height));
+    }
+
+    let scale = MOORED_BOAT_MAX_WIDTH as f32 / tex_width as f32;
+    (((anchor.0 as f32 * scale) as isize, (anchor.1 as f32 * scale) as isize),
+     ((tex_width as f32 * scale) as u32, (tex_height as f32 * scale) as u32))
+}
+
+pub struct IslandScene {
+    player_timer: i32,
+    player_last_pos: (isize, isize),
+    // tiles still to walk through after a click
+    path: VecDeque<(isize, isize)>,
+
+    camera: Camera,
+    bounds: Rect,
+    // last position seen, in screen pixels
+    mouse: Option<(i32, i32)>,
+    growth: GrowthSprites
+}
+
+impl IslandScene {
+    pub fn new(island: &Island, textures: &TextureRegistry) -> IslandScene {
+        let player = island.player();
+        let (x, y) = Camera::tile_center(player.x, player.y);
+        IslandScene{player_timer: 0, player_last_pos: (0, 0), path: VecDeque::new(),
+                    camera: Camera::new(x, y), bounds: map_bounds(island.map.len(), island.map[0].len()), mouse: None,
+                    growth: GrowthSprites::new(textures)}
+    }
+
+    fn view(&self, renderer: &dyn Renderer, alpha: f32) -> View {
+        let (w_width, w_height) = renderer.output_size();
+        self.camera.view(alpha, w_width, w_height, self.bounds)
+    }
+
+    // turns the player towards dir and takes a step that way unless something is in the way
+    fn walk(&mut self, island: &mut Island, dir: Direction) -> bool {
+        let player = island.player();
+        self.player_last_pos = (player.x, player.y);
+        island.player_mut().dir = dir;
+
+        let (dx, dy) = step_delta(dir);
+        let (x, y) = (player.x + dx, player.y + dy);
+        if tile_at(&island.map, x, y) == WATER || island.is_blocked(x, y) {
+            return false;
+        }
+
+        island.player_mut().x = x;
+        island.player_mut().y = y;
+        self.player_timer = 20;
+        true
+    }
+}
+
+impl Scene for IslandScene {
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        match *event {
+            Event::KeyUp { keycode: Some(Keycode::E), .. } => Some(Action::INTERACT),
+            Event::KeyUp { keycode: Some(Keycode::B), .. } => Some(Action::BUILD),
+            Event::KeyUp { keycode: Some(Keycode::R), .. } => Some(Action::PLANT),
+            Event::KeyUp { keycode: Some(Keycode::Plus), .. } | Event::KeyUp { keycode: Some(Keycode::Equals), .. } |
+            Event::KeyUp { keycode: Some(Keycode::KpPlus), .. } => Some(Action::ZOOM(1)),
+            Event::KeyUp { keycode: Some(Keycode::Minus), .. } | Event::KeyUp { keycode: Some(Keycode::KpMinus), .. } => Some(Action::ZOOM(-1)),
+            Event::MouseWheel { y, .. } if y != 0 => Some(Action::ZOOM(y.signum())),
+            Event::MouseMotion { x, y, .. } => {
+                self.mouse = Some((x, y));
+                None
+            },
+            Event::MouseButtonUp { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } => {
+                let (tile_x, tile_y) = self.view(renderer, 1.0).tile_at(x, y);
+                Some(Action::WALK(tile_x, tile_y))
+            },
+            _ => None
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        for action in &input.actions {
+            let island = &mut state.island;
+
+            match *action {
+                Action::INTERACT => {
+                    gather_resource(&state.textures, &state.balance, island, &mut state.player_boat);
+
+                    let player = island.player();
+                    if (player.x - island.dock.0).abs() <= 1 && (player.y - island.dock.1).abs() <= 1 && state.player_boat.obj.is_some() {
+                        let combat = CombatScene::new(&state.player_boat);
+                        return Transition::PUSH(Box::new(SplashScene::new("finalmente", 3 * TICKS_PER_SECOND, Box::new(combat))));
+                    }
+                },
+
+                Action::BUILD => return Transition::PUSH(Box::new(ShipyardScene::new(&state.shipyard))),
+
+                Action::PLANT => plant_sapling(&state.textures, &state.balance, island, &mut state.player_boat),
+
+                Action::ZOOM(steps) => self.camera.zoom_by(steps),
+
+                Action::WALK(x, y) => {
+                    let player = island.player();
+                    self.path = find_path(island, (player.x, player.y), (x, y)).unwrap_or_default().into();
+                },
+
+                _ => ()
+            }
+        }
+
+        let island = &mut state.island;
+        island.grow(&self.growth, &state.balance);
+
+        if self.player_timer > 0 {
+            self.player_timer -= 1;
+        }
+
+        // the keyboard takes over from a click
+        let walk_dirs = [Direction::NE, Direction::NW, Direction::SW, Direction::SE];
+        if walk_dirs.iter().any(|dir| input.is_walking(*dir)) {
+            self.path.clear();
+        }
+        for dir in &walk_dirs {
+            if input.is_walking(*dir) && self.player_timer == 0 {
+                self.walk(island, *dir);
+            }
+        }
+
+        if self.player_timer == 0 {
+            if let Some((x, y)) = self.path.pop_front() {
+                let player = island.player();
+                let delta = (x - player.x, y - player.y);
+                let walked = match walk_dirs.iter().cloned().find(|dir| step_delta(*dir) == delta) {
+                    Some(dir) => self.walk(island, dir),
+                    None => false
+                };
+                // something got in the way since the path was found
+                if !walked {
+                    self.path.clear();
+                }
+            }
+        }
+
+        let player = island.player();
+        let (x, y) = Camera::tile_center(player.x, player.y);
+        self.camera.follow(x, y);
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, alpha: f32) {
+        let island = &state.island;
+        let view = self.view(renderer, alpha);
+
+        renderer.clear(BG_COLOR);
+
+        {
+            let (width, height) = (island.map.len() as isize, island.map[0].len() as isize);
+            for y in 0..height {
+                for x in 0..width {
+                    let rect = view.tile_rect(x, y, (0, 0), (TILE_WIDTH as u32, TILE_HEIGHT as u32));
+                    renderer.draw_named(tile_texture(island.map[x as usize][y as usize]), rect);
+                }
+            }
+
+            // the tile a click would walk to
+            if let Some((mouse_x, mouse_y)) = self.mouse {
+                let (x, y) = view.tile_at(mouse_x, mouse_y);
+                if tile_at(&island.map, x, y) != WATER {
+                    renderer.fill_diamond(view.tile_rect(x, y, (0, 0), (TILE_WIDTH as u32, TILE_GROUND as u32)), HOVER_COLOR);
+                }
+            }
+
+            // back to front: the diagonals further up first, then on the same diagonal
+            // whatever reaches further down, the boat is sorted along with the rest and
+            // the player goes over a stump or sapling they're standing on
+            let mut sprites: Vec<_> = island.objects.iter().map(|(id, obj)| (Some(*id), *obj)).collect();
+            sprites.extend(state.player_boat.obj.map(|obj| (None, obj)));
+            sprites.sort_by_key(|(id, obj)| {
+                let (anchor, (_, height)) = placement(renderer, obj, id.is_none());
+                (obj.x + obj.y, anchor.1 + height as isize, *id == Some(island.player))
+            });
+
+            for (id, obj) in sprites {
+                let (anchor, size) = placement(renderer, &obj, id.is_none());
+
+                let mut offset = (0, 0);
+                if id == Some(island.player) {
+                    let dx = obj.x - self.player_last_pos.0;
+                    let dy = obj.y - self.player_last_pos.1;
+
+                    let ratio = ((self.player_timer as f32 - alpha) / 20.0).max(0.0);
+
+                    if dx == -1 {
+                        offset = ((ratio * HALF_TILE_WIDTH as f32) as isize, (ratio * HALF_TILE_HEIGHT as f32) as isize);
+                    }
+                    if dx == 1 {
+                        offset = (-(ratio * HALF_TILE_WIDTH as f32) as isize, -(ratio * HALF_TILE_HEIGHT as f32) as isize);
+                    }
+                    if dy == -1 {
+                        offset = (-(ratio * HALF_TILE_WIDTH as f32) as isize, (ratio * HALF_TILE_HEIGHT as f32) as isize);
+                    }
+                    if dy == 1 {
+                        offset = ((ratio * HALF_TILE_WIDTH as f32) as isize, -(ratio * HALF_TILE_HEIGHT as f32) as isize);
+                    }
+                }
+
+                let rect = view.tile_rect(obj.x, obj.y, (anchor.0 + offset.0, anchor.1 + offset.1), size);
+
+                renderer.draw_texture(obj.texture, obj.dir, rect);
+            }
+
+            // how far along the deposits being mined are, over everything else
+            for (id, hits) in &island.mined {
+                let obj = island.objects[id];
+                let (tex_width, tex_height) = renderer.texture_size(obj.texture, obj.dir);
+                let sprite = view.tile_rect(obj.x, obj.y, renderer.anchor(obj.texture), (tex_width, tex_height));
+
+                let bar = rect!(sprite.x(), sprite.y() - MINING_BAR_HEIGHT as i32 - 4, sprite.width(), MINING_BAR_HEIGHT);
+                renderer.fill_rect(bar, UI_BG_COLOR);
+                let done = bar.width() as isize * hits / state.balance.deposit_hits;
+                renderer.fill_rect(rect!(bar.x(), bar.y(), done.clamp(1, bar.width() as isize), bar.height()), MINING_BAR_COLOR);
+            }
+        }
+
+        draw_materials_hud(renderer, &state.player_boat, &state.balance);
+    }
+
+    fn resume(&mut self, _state: &mut GameState) {
+        self.player_timer = 0;
+        self.player_last_pos = (0, 0);
+        self.path.clear();
+    }
+}
@@ -0,0 +1,118 @@
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use crate::{GameState, BG_COLOR, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT, ACTION_HUD_BORDER};
+use crate::save::{self, SAVE_SLOTS};
+use crate::input::{Action, TickInput};
+use crate::render::{FontSize, Renderer};
+use crate::scene::{Scene, Transition, draw_button};
+use crate::scene::island::IslandScene;
+use crate::scene::combat::CombatScene;
+
+// (continue, new game) buttons of a slot
+fn slot_rects(renderer: &dyn Renderer, slot: usize) -> (sdl2::rect::Rect, sdl2::rect::Rect) {
+    let (w_width, w_height) = renderer.output_size();
+    let left = (w_width / 2).saturating_sub(BATTLE_RESULT_BUTTON_WIDTH + ACTION_HUD_BORDER);
+    let top = w_height / 3 + slot as u32 * (BATTLE_RESULT_BUTTON_HEIGHT + ACTION_HUD_BORDER * 4);
+
+    (rect!(left, top, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT),
+     rect!(left + BATTLE_RESULT_BUTTON_WIDTH + ACTION_HUD_BORDER * 2, top, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT))
+}
+
+// below the slots
+fn language_rect(renderer: &dyn Renderer) -> sdl2::rect::Rect {
+    let (w_width, _w_height) = renderer.output_size();
+    let (continue_rect, _new_rect) = slot_rects(renderer, SAVE_SLOTS);
+    rect!(w_width.saturating_sub(BATTLE_RESULT_BUTTON_WIDTH) / 2, continue_rect.y, BATTLE_RESULT_BUTTON_WIDTH, BATTLE_RESULT_BUTTON_HEIGHT)
+}
+
+// title screen, continue a saved game or start a new one in one of the slots
+pub struct MenuScene {
+    saves: Vec<bool>
+}
+
+impl MenuScene {
+    pub fn new() -> MenuScene {
+        MenuScene{saves: (0..SAVE_SLOTS).map(save::exists).collect()}
+    }
+}
+
+impl Scene for MenuScene {
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        if let Event::MouseButtonUp { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } = *event {
+            let r = language_rect(renderer);
+            if x >= r.x && x <= r.x + r.w && y >= r.y && y <= r.y + r.h {
+                return Some(Action::LANGUAGE);
+            }
+
+            for slot in 0..SAVE_SLOTS {
+                let (continue_rect, new_rect) = slot_rects(renderer, slot);
+
+                if self.saves[slot] && x >= continue_rect.x && x <= continue_rect.x + continue_rect.w &&
+                   y >= continue_rect.y && y <= continue_rect.y + continue_rect.h {
+                    match save::read(slot) {
+                        Ok(save) => return Some(Action::CONTINUE(slot, Box::new(save))),
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            self.saves[slot] = false;
+                        }
+                    }
+                }
+                else if x >= new_rect.x && x <= new_rect.x + new_rect.w && y >= new_rect.y && y <= new_rect.y + new_rect.h {
+                    return Some(Action::NEW(slot));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        for action in &input.actions {
+            match action {
+                Action::CONTINUE(slot, save) => {
+                    match save::restore(state, (**save).clone(), *slot) {
+                        // saves are made right after a battle, so we're back at sea
+                        Ok(()) => {
+                            let combat = CombatScene::new(&state.player_boat);
+                            return Transition::RESET(vec!(Box::new(IslandScene::new(&state.island, &state.textures)), Box::new(combat)));
+                        },
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            self.saves[*slot] = false;
+                        }
+                    }
+                },
+                Action::NEW(slot) => {
+                    state.slot = Some(*slot);
+                    return Transition::REPLACE(Box::new(IslandScene::new(&state.island, &state.textures)));
+                },
+                Action::LANGUAGE => state.locales.next(),
+                _ => ()
+            }
+        }
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, _alpha: f32) {
+        let (w_width, w_height) = renderer.output_size();
+
+        renderer.clear(BG_COLOR);
+
+        let title = state.locales.tr("title");
+        let (text_width, _text_height) = renderer.text_size(title, FontSize::NORMAL);
+        renderer.draw_text(title, FontSize::NORMAL, Color::RGBA(255, 255, 255, 255), (w_width as i32 - text_width as i32) / 2, (w_height / 6) as i32);
+
+        for slot in 0..SAVE_SLOTS {
+            let (continue_rect, new_rect) = slot_rects(renderer, slot);
+            if self.saves[slot] {
+                draw_button(renderer, continue_rect, &state.locales.format("continue_game", &[("slot", &(slot + 1))]));
+            }
+            draw_button(renderer, new_rect, &state.locales.format("new_game", &[("slot", &(slot + 1))]));
+        }
+
+        let rect = language_rect(renderer);
+        draw_button(renderer, rect, &state.locales.format("language", &[("language", &state.locales.name())]));
+    }
+}
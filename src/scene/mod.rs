@@ -0,0 +1,160 @@
+pub mod splash;
+pub mod menu;
+pub mod island;
+pub mod combat;
+pub mod battle_result;
+pub mod game_over;
+pub mod shipyard;
+
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use crate::input::{Action, TickInput};
+use crate::island::{GRASS, WATER, SAND};
+use crate::render::{FontSize, Renderer};
+use crate::{Boat, GameState, UI_BG_COLOR, UI_BUTTON_COLOR, HUD_FULL_COLOR};
+use crate::balance::Balance;
+use crate::inventory::ResourceKind;
+
+pub enum Transition {
+    NONE,
+    PUSH(Box<dyn Scene>),
+    POP,
+    REPLACE(Box<dyn Scene>),
+    // throws away the whole stack, the last scene ends up on top
+    RESET(Vec<Box<dyn Scene>>),
+    QUIT
+}
+
+pub trait Scene {
+    // only says what the player did, acting on it waits for update so a
+    // recorded session plays back the same
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action>;
+    // called once per tick, see TICKS_PER_SECOND
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition;
+
+    // alpha goes from 0 to 1 between two ticks, for smoothing movement
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, alpha: f32);
+
+    // called when the scene above this one is popped
+    fn resume(&mut self, _state: &mut GameState) {}
+
+    // overlays are drawn over the scene below them instead of replacing it
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>) -> SceneStack {
+        SceneStack{scenes: vec!(first)}
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    pub fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.input(&renderer.logical_event(event), renderer),
+            None => None
+        }
+    }
+
+    pub fn update(&mut self, state: &mut GameState, input: &TickInput) {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(state, input),
+            None => return
+        };
+        self.apply(transition, state);
+    }
+
+    pub fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, alpha: f32) {
+        if self.scenes.is_empty() {
+            return;
+        }
+
+        let mut first = self.scenes.len() - 1;
+        while first > 0 && self.scenes[first].is_overlay() {
+            first -= 1;
+        }
+
+        for scene in &self.scenes[first..] {
+            scene.draw(state, renderer, alpha);
+        }
+    }
+
+    fn apply(&mut self, transition: Transition, state: &mut GameState) {
+        match transition {
+            Transition::NONE => (),
+            Transition::PUSH(scene) => {
+                self.scenes.push(scene);
+            },
+            Transition::POP => {
+                self.scenes.pop();
+                if let Some(scene) = self.scenes.last_mut() {
+                    scene.resume(state);
+                }
+            },
+            Transition::REPLACE(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            },
+            Transition::RESET(scenes) => {
+                self.scenes = scenes;
+            },
+            Transition::QUIT => {
+                self.scenes.clear();
+            }
+        }
+    }
+}
+
+pub fn tile_texture(tile: usize) -> &'static str {
+    match tile {
+        GRASS => "grass",
+        WATER => "water",
+        SAND => "sand",
+        _ => panic!("unknown tile {}", tile)
+    }
+}
+
+// one row per resource the boat carries, wood and mineral always, full stacks stand out
+pub fn draw_materials_hud(renderer: &mut dyn Renderer, boat: &Boat, balance: &Balance) {
+    let (w_width, w_height) = renderer.output_size();
+
+    let kinds: Vec<_> = ResourceKind::ALL.iter().cloned()
+        .filter(|kind| *kind == ResourceKind::WOOD || *kind == ResourceKind::MINERAL || boat.inventory.get(*kind) > 0)
+        .collect();
+
+    let line_height = renderer.line_height(FontSize::NORMAL);
+    let rows = kinds.len() as u32;
+    let rect = rect!(w_width as i32 - 125, w_height as i32 - (rows * line_height + 10) as i32, 120, rows * line_height + 5);
+    renderer.fill_rect(rect, UI_BG_COLOR);
+
+    for (row, kind) in kinds.iter().enumerate() {
+        // counted up from the bottom
+        let bottom = w_height as i32 - ((rows - row as u32 - 1) * line_height) as i32 - 5;
+
+        let amount = boat.inventory.get(*kind).to_string();
+        let color = if boat.inventory.is_full(*kind, balance) { HUD_FULL_COLOR } else { Color::RGBA(255, 255, 255, 255) };
+        let (text_width, text_height) = renderer.text_size(&amount, FontSize::NORMAL);
+        renderer.draw_text(&amount, FontSize::NORMAL, color, w_width as i32 - text_width as i32 - 5, bottom - text_height as i32);
+
+        let (tex_width, tex_height) = renderer.named_size(kind.name());
+        let rect = rect!(w_width as i32 - 120, bottom - text_height as i32, tex_width as f32 * (text_height as f32 / tex_height as f32), text_height);
+        renderer.draw_named(kind.name(), rect);
+    }
+}
+
+pub fn draw_button(renderer: &mut dyn Renderer, bg_rect: sdl2::rect::Rect, text: &str) {
+    renderer.fill_rect(bg_rect, UI_BUTTON_COLOR);
+
+    let (text_width, text_height) = renderer.text_size(text, FontSize::SMALL);
+    renderer.draw_text(text, FontSize::SMALL, Color::RGBA(255, 255, 255, 255),
+                       bg_rect.x + bg_rect.w / 2 - text_width as i32 / 2, bg_rect.y + bg_rect.h / 2 - text_height as i32 / 2);
+}
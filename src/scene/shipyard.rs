@@ -0,0 +1,149 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use std::mem;
+
+use crate::{GameState, AttackType, Target, BATTLE_RESULT_BG_COLOR, ACTION_HUD_BORDER, ACTION_HUD_BUTTON_WIDTH, ACTION_HUD_BUTTON_HEIGHT,
+            SHIPYARD_WIDTH, SHIPYARD_ROW_HEIGHT};
+use crate::input::{Action, TickInput};
+use crate::render::{FontSize, Renderer};
+use crate::scene::{Scene, Transition, draw_button};
+use crate::shipyard::{Hull, Shipyard};
+
+// the panel and the button of every hull, in the middle of the screen
+fn layout(renderer: &dyn Renderer, hulls: usize) -> (Rect, Vec<Rect>) {
+    let (w_width, w_height) = renderer.output_size();
+    let title_height = renderer.line_height(FontSize::NORMAL);
+    let help_height = renderer.line_height(FontSize::SMALL);
+
+    let height = ACTION_HUD_BORDER * 2 + title_height + hulls as u32 * SHIPYARD_ROW_HEIGHT + help_height;
+    let (left, top) = (w_width.saturating_sub(SHIPYARD_WIDTH) / 2, w_height.saturating_sub(height) / 2);
+
+    let buttons = (0..hulls as u32)
+        .map(|i| rect!(left + ACTION_HUD_BORDER, top + ACTION_HUD_BORDER + title_height + i * SHIPYARD_ROW_HEIGHT,
+                       ACTION_HUD_BUTTON_WIDTH, ACTION_HUD_BUTTON_HEIGHT))
+        .collect();
+    (rect!(left, top, SHIPYARD_WIDTH, height), buttons)
+}
+
+// health, shield, cannons and attacks besides firing
+fn hull_stats(state: &GameState, hull: &Hull) -> String {
+    let cannons = hull.parts.iter().filter(|part| **part == Target::CANNON1 || **part == Target::CANNON2).count();
+    let mut text = state.locales.format("hull_stats", &[("health", &hull.health), ("shield", &hull.shield), ("cannons", &cannons)]);
+    for attack in &hull.attacks {
+        let name = match *attack {
+            AttackType::NET => "net",
+            AttackType::HARPOON => "harpoon",
+            AttackType::NORMAL => continue
+        };
+        text.push_str(", ");
+        text.push_str(state.locales.tr(name));
+    }
+
+    text
+}
+
+// shown over the island with B, click a hull (or press its number) to trade
+// the boat in for it, resources carry over
+pub struct ShipyardScene {
+    hulls: usize
+}
+
+impl ShipyardScene {
+    pub fn new(shipyard: &Shipyard) -> ShipyardScene {
+        ShipyardScene{hulls: shipyard.hulls.len()}
+    }
+}
+
+impl Scene for ShipyardScene {
+    fn input(&mut self, event: &Event, renderer: &dyn Renderer) -> Option<Action> {
+        let number_keys = [Keycode::Num1, Keycode::Num2, Keycode::Num3, Keycode::Num4, Keycode::Num5,
+                           Keycode::Num6, Keycode::Num7, Keycode::Num8, Keycode::Num9];
+
+        match *event {
+            Event::KeyUp { keycode: Some(Keycode::Escape), .. } | Event::KeyUp { keycode: Some(Keycode::B), .. } => Some(Action::CLOSE),
+            Event::KeyUp { keycode: Some(key), .. } => {
+                match number_keys.iter().position(|k| *k == key) {
+                    Some(i) if i < self.hulls => Some(Action::CHOICE(i)),
+                    _ => None
+                }
+            },
+            Event::MouseButtonUp { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } => {
+                let (_panel, buttons) = layout(renderer, self.hulls);
+                buttons.iter().position(|r| x >= r.x && x <= r.x + r.w && y >= r.y && y <= r.y + r.h).map(Action::CHOICE)
+            },
+            _ => None
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState, input: &TickInput) -> Transition {
+        for action in &input.actions {
+            match *action {
+                Action::CHOICE(i) => {
+                    let hull = match state.shipyard.hulls.get(i) {
+                        Some(hull) => hull,
+                        None => continue
+                    };
+
+                    // not enough of something, the cost is shown in red
+                    if !state.player_boat.inventory.take_all(&hull.cost) {
+                        continue;
+                    }
+
+                    let inventory = mem::take(&mut state.player_boat.inventory);
+                    state.player_boat = hull.build(&state.textures, inventory, state.island.dock.0, state.island.dock.1);
+                    return Transition::POP;
+                },
+                Action::CLOSE => return Transition::POP,
+                _ => ()
+            }
+        }
+
+        Transition::NONE
+    }
+
+    fn draw(&self, state: &GameState, renderer: &mut dyn Renderer, _alpha: f32) {
+        let (panel, buttons) = layout(renderer, self.hulls);
+        renderer.fill_rect(panel, BATTLE_RESULT_BG_COLOR);
+
+        let title = state.locales.tr("shipyard");
+        let (text_width, _text_height) = renderer.text_size(title, FontSize::NORMAL);
+        renderer.draw_text(title, FontSize::NORMAL, Color::RGBA(255, 255, 255, 255),
+                           panel.x() + (panel.width() as i32 - text_width as i32) / 2, panel.y() + ACTION_HUD_BORDER as i32);
+
+        let line_height = renderer.line_height(FontSize::NORMAL);
+        for (hull, button) in state.shipyard.hulls.iter().zip(&buttons) {
+            draw_button(renderer, *button, state.locales.tr(&hull.name));
+
+            // the cost, whatever the boat doesn't have enough of in red
+            let mut x = button.right() + ACTION_HUD_BORDER as i32 * 2;
+            for (kind, amount) in &hull.cost {
+                let (tex_width, tex_height) = renderer.named_size(kind.name());
+                let icon_width = (tex_width as f32 * (line_height as f32 / tex_height as f32)) as i32;
+                renderer.draw_named(kind.name(), rect!(x, button.y(), icon_width, line_height));
+                x += icon_width + ACTION_HUD_BORDER as i32;
+
+                let text = amount.to_string();
+                let color = if state.player_boat.inventory.get(*kind) >= *amount { Color::RGBA(255, 255, 255, 255) } else { Color::RGBA(255, 55, 55, 255) };
+                let (text_width, _text_height) = renderer.text_size(&text, FontSize::NORMAL);
+                renderer.draw_text(&text, FontSize::NORMAL, color, x, button.y());
+                x += text_width as i32 + ACTION_HUD_BORDER as i32 * 4;
+            }
+
+            let stats = hull_stats(state, hull);
+            renderer.draw_text(&stats, FontSize::SMALL, Color::RGBA(255, 255, 255, 255),
+                               button.right() + ACTION_HUD_BORDER as i32 * 2, button.y() + line_height as i32);
+        }
+
+        let help = state.locales.tr("shipyard_help");
+        let (text_width, text_height) = renderer.text_size(help, FontSize::SMALL);
+        renderer.draw_text(help, FontSize::SMALL, Color::RGBA(255, 255, 255, 255),
+                           panel.x() + (panel.width() as i32 - text_width as i32) / 2, panel.bottom() - ACTION_HUD_BORDER as i32 - text_height as i32);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,45 @@
+use sdl2::event::Event;
+
+use crate::{GameState, BG_COLOR};
+use crate::input::{Action, TickInput};
+use crate::render::Renderer;
+use crate::scene::{Scene, Transition};
+
+// full screen image shown for a while before moving on, used for the intro
+// and before sailing off to battle
+pub struct SplashScene {
+    texture: &'static str,
+    ticks_left: u32,
+    next: Option<Box<dyn Scene>>
+}
+
+impl SplashScene {
+    pub fn new(texture: &'static str, ticks: u32, next: Box<dyn Scene>) -> SplashScene {
+        SplashScene{texture, ticks_left: ticks, next: Some(next)}
+    }
+}
+
+impl Scene for SplashScene {
+    fn input(&mut self, _event: &Event, _renderer: &dyn Renderer) -> Option<Action> {
+        None
+    }
+
+    fn update(&mut self, _state: &mut GameState, _input: &TickInput) -> Transition {
+        if self.ticks_left > 0 {
+            self.ticks_left -= 1;
+            return Transition::NONE;
+        }
+
+        match self.next.take() {
+            Some(next) => Transition::REPLACE(next),
+            None => Transition::POP
+        }
+    }
+
+    fn draw(&self, _state: &GameState, renderer: &mut dyn Renderer, _alpha: f32) {
+        let (w_width, w_height) = renderer.output_size();
+
+        renderer.clear(BG_COLOR);
+        renderer.draw_named(self.texture, rect!(0, 0, w_width, w_height));
+    }
+}
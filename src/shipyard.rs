@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::{Boat, Object, AttackType, Target, check_ship, errors_to_result};
+use crate::balance::Balance;
+use crate::inventory::{Inventory, ResourceKind};
+use crate::textures::{Direction, TextureRegistry};
+
+// one boat the shipyard can build, see assets/hulls.ron
+#[derive (Clone, Deserialize)]
+pub struct Hull {
+    // key of its name in the locales
+    pub name: String,
+    pub cost: BTreeMap<ResourceKind, isize>,
+
+    pub health: isize,
+    pub shield: isize,
+    pub attacks: Vec<AttackType>,
+    pub parts: Vec<Target>,
+    sprite: String
+}
+
+impl Hull {
+    // launched at x, y with full health, carrying inventory
+    pub fn build(&self, textures: &TextureRegistry, inventory: Inventory, x: isize, y: isize) -> Boat {
+        Boat::new(self.health, self.health, self.shield, &self.attacks, &self.parts, inventory,
+                  Some(Object{texture: textures.id(&self.sprite), dir: Direction::SE, x, y}))
+    }
+}
+
+#[derive (Clone, Deserialize)]
+pub struct Shipyard {
+    pub hulls: Vec<Hull>
+}
+
+impl Shipyard {
+    // reads and checks the hulls
+    pub fn load(path: &str, textures: &TextureRegistry, balance: &Balance) -> Result<Shipyard, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let shipyard: Shipyard = ron::de::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut errors = Vec::new();
+        if shipyard.hulls.is_empty() {
+            errors.push("at least one hull is needed".to_owned());
+        }
+        for h in &shipyard.hulls {
+            for (kind, amount) in &h.cost {
+                if *amount < 0 {
+                    errors.push(format!("\"{}\": {:?} cost can't be negative", h.name, kind));
+                }
+                // it could never be paid for
+                if *amount > balance.stack_limit(*kind) {
+                    errors.push(format!("\"{}\": {:?} cost is over the stack limit in the balance", h.name, kind));
+                }
+            }
+            if h.health < 1 || h.shield < 0 {
+                errors.push(format!("\"{}\": health has to be at least 1 and shield can't be negative", h.name));
+            }
+            check_ship(&h.name, &h.attacks, &h.parts, &mut errors);
+            match textures.find(&h.sprite) {
+                Some(id) => {
+                    if !textures.has_variant(id, Direction::SE) {
+                        errors.push(format!("\"{}\": sprite \"{}\" needs an SE variant", h.name, h.sprite));
+                    }
+                },
+                None => errors.push(format!("\"{}\": sprite \"{}\" isn't in the manifest", h.name, h.sprite))
+            }
+        }
+
+        errors_to_result(path, errors, shipyard)
+    }
+
+    // wood the cheapest hull takes, islands have enough trees for it
+    pub fn wood_needed(&self) -> isize {
+        self.hulls.iter().map(|h| h.cost.get(&ResourceKind::WOOD).cloned().unwrap_or(0)).min().unwrap_or(0)
+    }
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::errors_to_result;
+use crate::inventory::ResourceKind;
+
+#[derive (Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum Direction {
+    NONE,
+    NE,
+    NW,
+    SW,
+    SE
+}
+
+// handle to a named sprite, together with a direction it picks the actual texture
+#[derive (Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub struct TextureId(usize);
+
+// sprites the code asks for by name and the directions it draws them in, the
+// resource icons are required as well, see ResourceKind::name
+const REQUIRED_SPRITES: &[(&str, &[Direction])] = &[
+    ("grass", &[Direction::NONE]),
+    ("water", &[Direction::NONE]),
+    ("sand", &[Direction::NONE]),
+    ("tree", &[Direction::NONE]),
+    ("stump", &[Direction::NONE]),
+    ("sapling", &[Direction::NONE]),
+    ("mineral_deposit", &[Direction::NONE]),
+    ("player", &[Direction::NE, Direction::NW, Direction::SW, Direction::SE]),
+    // the first enemy, and the player's boat if it's stolen
+    ("boat_small", &[Direction::NW, Direction::SE]),
+    ("ball", &[Direction::NONE]),
+    ("steerwheel", &[Direction::NONE]),
+    ("steerwheel_dark", &[Direction::NONE]),
+    ("steerwheel_silver", &[Direction::NONE]),
+    ("instructions", &[Direction::NONE]),
+    ("finalmente", &[Direction::NONE]),
+    ("gameover", &[Direction::NONE])
+];
+
+// one entry of assets/manifest.ron
+#[derive (Clone, Deserialize)]
+struct Sprite {
+    name: String,
+    file: String,
+    #[serde(default)]
+    anchor: (isize, isize),
+    #[serde(default)]
+    variants: Vec<Direction>
+}
+
+#[derive (Deserialize)]
+struct Manifest {
+    sprites: Vec<Sprite>
+}
+
+// what the manifest says about every sprite, doesn't touch SDL so the game
+// state can hold one to look sprites up by name
+#[derive (Clone)]
+pub struct TextureRegistry {
+    sprites: Vec<Sprite>,
+    // sprite files are relative to the manifest
+    dir: PathBuf
+}
+
+impl TextureRegistry {
+    // reads and checks the manifest
+    pub fn load(manifest_path: &str) -> Result<TextureRegistry, String> {
+        let text = fs::read_to_string(manifest_path).map_err(|e| format!("{}: {}", manifest_path, e))?;
+        let manifest: Manifest = ron::de::from_str(&text).map_err(|e| format!("{}: {}", manifest_path, e))?;
+        let dir = Path::new(manifest_path).parent().map(Path::to_path_buf).unwrap_or_default();
+        let registry = TextureRegistry{sprites: manifest.sprites, dir};
+
+        let mut errors = Vec::new();
+        for (i, sprite) in registry.sprites.iter().enumerate() {
+            if registry.sprites[..i].iter().any(|s| s.name == sprite.name) {
+                errors.push(format!("\"{}\" is listed twice", sprite.name));
+            }
+            if sprite.variants.contains(&Direction::NONE) {
+                errors.push(format!("\"{}\": NONE is not a variant, leave variants out instead", sprite.name));
+            }
+            if sprite.variants.is_empty() == sprite.file.contains("{}") {
+                errors.push(format!("\"{}\": file should contain {{}} exactly when the sprite has variants", sprite.name));
+            }
+            for dir in registry.directions(TextureId(i)) {
+                let path = registry.path(TextureId(i), dir);
+                if !Path::new(&path).is_file() {
+                    errors.push(format!("\"{}\": {} doesn't exist", sprite.name, path));
+                }
+            }
+        }
+
+        let icons = ResourceKind::ALL.iter().map(|kind| (kind.name(), &[Direction::NONE][..]));
+        for (name, dirs) in REQUIRED_SPRITES.iter().cloned().chain(icons) {
+            match registry.find(name) {
+                Some(id) => {
+                    for dir in dirs.iter().filter(|dir| !registry.has_variant(id, **dir)) {
+                        match dir {
+                            Direction::NONE => errors.push(format!("\"{}\": the game draws it without a direction, leave variants out", name)),
+                            _ => errors.push(format!("\"{}\": the game needs a {:?} variant", name, dir))
+                        }
+                    }
+                },
+                None => errors.push(format!("\"{}\" is missing, the game draws it", name))
+            }
+        }
+
+        errors_to_result(manifest_path, errors, registry)
+    }
+
+    pub fn find(&self, name: &str) -> Option<TextureId> {
+        self.sprites.iter().position(|s| s.name == name).map(TextureId)
+    }
+
+    pub fn id(&self, name: &str) -> TextureId {
+        match self.find(name) {
+            Some(id) => id,
+            None => panic!("unknown texture \"{}\", is it in the manifest?", name)
+        }
+    }
+
+    pub fn has_variant(&self, id: TextureId, dir: Direction) -> bool {
+        self.directions(id).contains(&dir)
+    }
+
+    pub fn name(&self, id: TextureId) -> &str {
+        &self.sprites[id.0].name
+    }
+
+    pub fn anchor(&self, id: TextureId) -> (isize, isize) {
+        self.sprites[id.0].anchor
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.sprites.iter().map(|s| s.name.clone()).collect()
+    }
+
+    // id from a registry with the given names to the same sprite in this one
+    pub fn remap(&self, id: TextureId, names: &[String]) -> Result<TextureId, String> {
+        match names.get(id.0) {
+            Some(name) => self.find(name).ok_or(format!("sprite \"{}\" is no longer in the manifest", name)),
+            None => Err(format!("texture id {} out of range", id.0))
+        }
+    }
+
+    // every image the manifest lists, with the sprite and direction it's for
+    pub fn files(&self) -> Vec<(TextureId, Direction, String)> {
+        (0..self.sprites.len()).map(TextureId)
+            .flat_map(|id| self.directions(id).into_iter().map(move |dir| (id, dir)))
+            .map(|(id, dir)| (id, dir, self.path(id, dir)))
+            .collect()
+    }
+
+    fn directions(&self, id: TextureId) -> Vec<Direction> {
+        let variants = &self.sprites[id.0].variants;
+        if variants.is_empty() {
+            vec!(Direction::NONE)
+        }
+        else {
+            variants.clone()
+        }
+    }
+
+    fn path(&self, id: TextureId, dir: Direction) -> String {
+        let file = &self.sprites[id.0].file;
+        let path = match dir {
+            Direction::NONE => self.dir.join(file),
+            _ => self.dir.join(file.replace("{}", &format!("{:?}", dir)))
+        };
+        path.to_string_lossy().into_owned()
+    }
+}